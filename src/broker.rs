@@ -0,0 +1,407 @@
+//! A small client of the NGSI-LD API of a context broker, used by the app and the CLI.
+
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::{fs, io::Write, path::PathBuf};
+
+use reqwest::{header::CONTENT_TYPE, Method, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::config::config_dir;
+
+/// How many entities are fetched per request, brokers limit it to 1000 at most.
+const PAGE_SIZE: usize = 100;
+
+/// How to reach a broker, saved as a yaml file in the config directory.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BrokerSettings {
+    /// E.g. `http://localhost:1026`, the NGSI-LD paths are appended to it.
+    pub base_url: String,
+    /// Sent as `NGSILD-Tenant` header, if not empty.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub tenant: String,
+    /// Sent as bearer token, if not empty.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token: String,
+}
+
+impl BrokerSettings {
+    pub fn load_saved() -> Result<Self, anyhow::Error> {
+        let yaml = fs::read_to_string(settings_path())?;
+        Ok(serde_yaml::from_str(&yaml)?)
+    }
+
+    /// Saves the settings, only the user may read them as they hold the token.
+    pub fn save(&self) -> Result<PathBuf, anyhow::Error> {
+        let path = settings_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&path)?;
+        // The mode only applies to new files
+        #[cfg(unix)]
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(serde_yaml::to_string(self)?.as_bytes())?;
+        Ok(path)
+    }
+}
+
+pub fn settings_path() -> PathBuf {
+    config_dir().join("broker.yaml")
+}
+
+/// A request to the broker and its response, as it is shown to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange {
+    pub method: String,
+    pub url: String,
+    /// The headers which were set, the token is masked.
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: Option<String>,
+    pub status: u16,
+    pub response_body: String,
+}
+
+impl Exchange {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The exchange in the form of a raw HTTP message, with pretty printed json bodies.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{} {}\n", self.method, self.url);
+        for (name, value) in self.request_headers.iter() {
+            text.push_str(&format!("{name}: {value}\n"));
+        }
+        if let Some(body) = &self.request_body {
+            text.push_str(&format!("\n{body}\n"));
+        }
+        text.push_str(&format!("\n=> {}\n", self.status));
+        if !self.response_body.is_empty() {
+            text.push_str(&format!("\n{}\n", pretty(&self.response_body)));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Broker {
+    settings: BrokerSettings,
+    client: reqwest::Client,
+}
+
+impl Broker {
+    pub fn new(settings: BrokerSettings) -> Self {
+        Broker {
+            settings,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.settings.base_url
+    }
+
+    /// Creates the entity, brokers answer with 409 if it already exists.
+    pub async fn create(&self, entity: &Value) -> Result<Exchange, anyhow::Error> {
+        self.send(Method::POST, &["entities"], &[], Some(entity))
+            .await
+    }
+
+    /// Creates the entities or replaces the ones which exist, `entities` is an entity or an array.
+    pub async fn upsert(&self, entities: &Value) -> Result<Exchange, anyhow::Error> {
+        let batch = match entities {
+            Value::Array(_) => entities.clone(),
+            entity => json!([entity]),
+        };
+        self.send(
+            Method::POST,
+            &["entityOperations", "upsert"],
+            &[],
+            Some(&batch),
+        )
+        .await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<Exchange, anyhow::Error> {
+        self.send(Method::DELETE, &["entities", id], &[], None)
+            .await
+    }
+
+    /// The types of the entities in the broker, as the broker names them.
+    pub async fn types(&self) -> Result<Vec<String>, anyhow::Error> {
+        let list = self.get(&["types"], &[]).await?;
+        let types = list["typeList"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("the broker answered with no list of types"))?;
+        Ok(types
+            .iter()
+            .filter_map(|typ| typ.as_str().map(str::to_string))
+            .collect())
+    }
+
+    /// Every entity of type `typ` in normalized form, fetched page by page.
+    pub async fn entities(&self, typ: &str) -> Result<Vec<Value>, anyhow::Error> {
+        let limit = PAGE_SIZE.to_string();
+        let mut entities = vec![];
+        loop {
+            let offset = entities.len().to_string();
+            let query = [("type", typ), ("limit", &limit), ("offset", &offset)];
+            let Value::Array(page) = self.get(&["entities"], &query).await? else {
+                anyhow::bail!("the broker answered with no list of entities");
+            };
+            let is_last = page.len() < PAGE_SIZE;
+            entities.extend(page);
+            if is_last {
+                return Ok(entities);
+            }
+        }
+    }
+
+    /// The types in the broker with their entities.
+    pub async fn entities_by_type(&self) -> Result<Vec<(String, Vec<Value>)>, anyhow::Error> {
+        let mut deployment = vec![];
+        for typ in self.types().await? {
+            let entities = self.entities(&typ).await?;
+            deployment.push((typ, entities));
+        }
+        Ok(deployment)
+    }
+
+    /// Reads json from the broker, unlike `send` error statuses are errors.
+    async fn get(&self, path: &[&str], query: &[(&str, &str)]) -> Result<Value, anyhow::Error> {
+        let exchange = self.send(Method::GET, path, query, None).await?;
+        if !exchange.is_success() {
+            anyhow::bail!(
+                "{} answered with {}: {}",
+                exchange.url,
+                exchange.status,
+                exchange.response_body
+            );
+        }
+        Ok(serde_json::from_str(&exchange.response_body)?)
+    }
+
+    /// Sends a request to a path below `/ngsi-ld/v1`. Answers with error statuses are
+    /// exchanges as well, only failing connections are errors.
+    async fn send(
+        &self,
+        method: Method,
+        path: &[&str],
+        query: &[(&str, &str)],
+        body: Option<&Value>,
+    ) -> Result<Exchange, anyhow::Error> {
+        let url = self.url(path, query)?;
+        let mut headers = vec![];
+        if let Some(body) = body {
+            // Entities with their own context must not be sent as plain json
+            let content_type = if has_context(body) {
+                "application/ld+json"
+            } else {
+                "application/json"
+            };
+            headers.push((CONTENT_TYPE.to_string(), content_type.to_string()));
+        }
+        if !self.settings.tenant.is_empty() {
+            headers.push(("NGSILD-Tenant".to_string(), self.settings.tenant.clone()));
+        }
+
+        let mut request = self.client.request(method.clone(), url.clone());
+        for (name, value) in headers.iter() {
+            request = request.header(name, value);
+        }
+        if !self.settings.token.is_empty() {
+            request = request.bearer_auth(&self.settings.token);
+            headers.push(("Authorization".to_string(), "Bearer ***".to_string()));
+        }
+        let request_body = body.map(|body| serde_json::to_string_pretty(body).unwrap());
+        if let Some(body) = &request_body {
+            request = request.body(body.clone());
+        }
+
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        let response_body = response.text().await?;
+
+        Ok(Exchange {
+            method: method.to_string(),
+            url: url.to_string(),
+            request_headers: headers,
+            request_body,
+            status,
+            response_body,
+        })
+    }
+
+    fn url(&self, path: &[&str], query: &[(&str, &str)]) -> Result<Url, anyhow::Error> {
+        let mut url = Url::parse(self.settings.base_url.trim())?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("{} can not be a base url", self.settings.base_url))?
+            .pop_if_empty()
+            .extend(["ngsi-ld", "v1"])
+            .extend(path);
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(url)
+    }
+}
+
+fn has_context(body: &Value) -> bool {
+    match body {
+        Value::Array(entities) => entities.iter().any(has_context),
+        Value::Object(entity) => entity.contains_key("@context"),
+        _ => false,
+    }
+}
+
+fn pretty(body: &str) -> String {
+    serde_json::from_str::<Value>(body)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc::{channel, Receiver},
+        thread,
+    };
+
+    use serde_json::json;
+
+    use super::{Broker, BrokerSettings};
+
+    /// A broker on a local port, which answers the requests in turn with the given status and
+    /// body and passes on the raw requests it got.
+    fn mock_broker(responses: Vec<(u16, &'static str)>) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body_bytes = vec![0; content_length];
+                reader.read_exact(&mut body_bytes).unwrap();
+                request.push_str(&String::from_utf8(body_bytes).unwrap());
+                sender.send(request).unwrap();
+
+                let response = format!(
+                    "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\n\
+                    Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (base_url, receiver)
+    }
+
+    #[test]
+    fn send_entities() {
+        let (base_url, requests) = mock_broker(vec![
+            (201, ""),
+            (204, ""),
+            (
+                404,
+                r#"{"type":"https://uri.etsi.org/ngsi-ld/errors/ResourceNotFound"}"#,
+            ),
+        ]);
+        let broker = Broker::new(BrokerSettings {
+            base_url,
+            tenant: "city".to_string(),
+            token: "secret".to_string(),
+        });
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let entity = json!({"id": "urn:ngsi-ld:Device:1", "type": "Device", "@context": []});
+        let created = runtime.block_on(broker.create(&entity)).unwrap();
+        assert!(created.is_success());
+        let request = requests.recv().unwrap().to_lowercase();
+        assert!(request.starts_with("post /ngsi-ld/v1/entities http/1.1\r\n"));
+        assert!(request.contains("content-type: application/ld+json\r\n"));
+        assert!(request.contains("ngsild-tenant: city\r\n"));
+        assert!(request.contains("authorization: bearer secret\r\n"));
+        assert!(request.ends_with("\"type\": \"device\",\n  \"@context\": []\n}"));
+        assert!(created
+            .request_headers
+            .contains(&("Authorization".to_string(), "Bearer ***".to_string())));
+
+        runtime.block_on(broker.upsert(&entity)).unwrap();
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /ngsi-ld/v1/entityOperations/upsert HTTP/1.1"));
+        assert!(request.ends_with("\"@context\": []\n  }\n]"));
+
+        let deleted = runtime
+            .block_on(broker.delete("urn:ngsi-ld:Device:1"))
+            .unwrap();
+        assert!(!deleted.is_success());
+        assert!(deleted.to_text().contains("=> 404"));
+        assert!(deleted.response_body.contains("ResourceNotFound"));
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("DELETE /ngsi-ld/v1/entities/urn:ngsi-ld:Device:1 HTTP/1.1"));
+    }
+
+    #[test]
+    fn browse_entities() {
+        let (base_url, requests) = mock_broker(vec![
+            (
+                200,
+                r#"{"id":"urn:ngsi-ld:EntityTypeList:1","type":"EntityTypeList","typeList":["Device"]}"#,
+            ),
+            (200, r#"[{"id":"urn:ngsi-ld:Device:1","type":"Device"}]"#),
+            (500, r#"{"title":"down"}"#),
+        ]);
+        let broker = Broker::new(BrokerSettings {
+            base_url,
+            ..Default::default()
+        });
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let deployment = runtime.block_on(broker.entities_by_type()).unwrap();
+        assert_eq!(
+            deployment,
+            [(
+                "Device".to_string(),
+                vec![json!({"id": "urn:ngsi-ld:Device:1", "type": "Device"})]
+            )]
+        );
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("GET /ngsi-ld/v1/types HTTP/1.1"));
+        assert!(requests
+            .recv()
+            .unwrap()
+            .starts_with("GET /ngsi-ld/v1/entities?type=Device&limit=100&offset=0 HTTP/1.1"));
+
+        let error = runtime.block_on(broker.types()).unwrap_err();
+        assert!(error.to_string().contains("answered with 500"));
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
+use dioxus::prelude::*;
+
 use crate::{
-    smartdata::models::{Model, ParsedModel},
+    profile::Profile,
+    smartdata::models::{Model, ParsedModel, Property},
     ModelData,
 };
 
@@ -25,16 +28,67 @@ impl ModelCache {
         }
     }
 
+    pub fn set_variant(&mut self, key: &str, index: usize, variant: String) {
+        if let Some(parsed_model) = self.inner.get_mut(key) {
+            if let Some(property) = parsed_model.properties.get_mut(index) {
+                property.variant = Some(variant);
+            }
+        }
+    }
+
+    /// Applies the selection of `profile` to all cached models it knows.
+    pub fn apply_profile(&mut self, profile: &Profile) {
+        for parsed_model in self.inner.values_mut() {
+            profile.apply(parsed_model);
+        }
+    }
+
+    /// Records the selection of all cached models, which were fetched from a repo.
+    pub fn record_profile(&self, name: &str) -> Profile {
+        let mut profile = Profile::new(name);
+        for parsed_model in self.inner.values().filter(|m| !m.repo.is_empty()) {
+            profile.record(parsed_model);
+        }
+        profile
+    }
+
     pub fn get(&self, key: &str) -> Option<&ParsedModel> {
         self.inner.get(key)
     }
 
+    /// All cached models sorted by name.
+    pub fn models(&self) -> Vec<&ParsedModel> {
+        let mut models: Vec<_> = self.inner.values().collect();
+        models.sort_by(|a, b| a.name.cmp(&b.name));
+        models
+    }
+
+    /// Inserts a model, replacing the cached one of the same name.
+    pub fn insert(&mut self, model: ParsedModel) {
+        self.inner.insert(model.name.clone(), model);
+    }
+
+    /// Every cached model, except `key`, which defines a property named `property_name`.
+    pub fn definitions_of(&self, key: &str, property_name: &str) -> Vec<(&ParsedModel, &Property)> {
+        let mut definitions: Vec<_> = self
+            .inner
+            .iter()
+            .filter(|(name, _)| name.as_str() != key)
+            .filter_map(|(_, model)| Some((model, model.property(property_name)?)))
+            .collect();
+        definitions.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+        definitions
+    }
+
     pub async fn get_or_fetch_and_insert(
         &mut self,
         model_data: &ModelData,
     ) -> Result<ParsedModel, anyhow::Error> {
+        // Only one ref of a model is cached, pinning another ref replaces it
         if let Some(cached_model) = self.inner.get(&model_data.name) {
-            return Ok(cached_model.clone());
+            if cached_model.git_ref == model_data.git_ref {
+                return Ok(cached_model.clone());
+            }
         }
 
         let res = Model::fetch_and_parse(model_data).await;
@@ -48,3 +102,60 @@ impl ModelCache {
         res
     }
 }
+
+/// Fetches every model which is not cached at its ref yet into `cache`.
+/// Models which fail to fetch are skipped, their errors are returned.
+pub async fn fetch_missing(mut cache: Signal<ModelCache>, models: Vec<ModelData>) -> Vec<String> {
+    let mut errors = vec![];
+    for model_data in models {
+        let cached = cache
+            .read()
+            .get(&model_data.name)
+            .is_some_and(|cached| cached.git_ref == model_data.git_ref);
+        if cached {
+            continue;
+        }
+
+        // The cache must not stay borrowed while fetching, components read it meanwhile
+        match Model::fetch_and_parse(&model_data).await {
+            Ok(model) => cache.write().insert(model),
+            Err(err) => errors.push(format!("{}: {err}", model_data.name)),
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::ModelCache;
+
+    #[test]
+    fn find_definitions() {
+        let model = |name: &str, properties: &[&str]| ParsedModel {
+            name: name.to_string(),
+            properties: properties
+                .iter()
+                .map(|name| Property {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let mut cache = ModelCache::new();
+        cache.insert(model("Vehicle", &["speed", "name"]));
+        cache.insert(model("Drone", &["speed"]));
+        cache.insert(model("Building", &["name"]));
+
+        let definitions: Vec<_> = cache
+            .definitions_of("Vehicle", "speed")
+            .into_iter()
+            .map(|(model, property)| (model.name.as_str(), property.name.as_str()))
+            .collect();
+        assert_eq!(definitions, [("Drone", "speed")]);
+        assert!(cache.definitions_of("Drone", "speed").len() == 1);
+        assert!(cache.definitions_of("Vehicle", "heading").is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+use std::{fs, path::Path};
+
+use serde_json::Value;
+
+use crate::{
+    broker::{settings_path, Broker, BrokerSettings, Exchange},
+    cache::ModelCache,
+    ngsi::{
+        conformance::{short_name, ConformanceReport, TypeReport},
+        convert::{convert, detect},
+        mapping::{CsvMapping, CsvTable},
+        Format,
+    },
+    smartdata::{
+        diff::ModelDiff,
+        models::{Model, ModelList},
+        refs::DEFAULT_REF,
+    },
+    ModelData,
+};
+
+const USAGE: &str = "\
+Usage: sdm_browser <command> [args]
+
+Commands:
+  diff <repo> <model> <old-ref> <new-ref>
+      Diffs a model between two git refs of its dataModel.<repo> repository
+      and prints a JSON report. Exits with 1 if there are breaking changes.
+  broker create <entity.json>
+  broker upsert <entity.json>
+  broker delete <entity-id>
+      Sends to the NGSI-LD broker configured in the app and prints the HTTP
+      exchange. Upsert takes an entity or an array of entities. Exits with 2
+      if the broker answers with an error.
+  broker check [ref]
+      Checks every entity in the broker against the smart data model of its
+      type at the git ref, master by default, and prints a JSON conformance
+      report. Exits with 1 if an entity does not conform or a type has no
+      model to check it against.
+  csv <mapping.yaml> <data.csv>
+      Converts the rows of a csv file into entities with a mapping saved in
+      the app and prints them as a JSON array. Invalid rows are left out and
+      their errors printed to stderr, then it exits with 2.
+  convert <format> <entity.json> [ref]
+      Converts an entity or an array of entities into normalized, key-values
+      or v2-normalized form and prints it. The smart data model of the type
+      at the git ref, master by default, decides how attributes are wrapped.
+  help
+      Prints this message.
+
+Exit codes: 0 = success, 1 = breaking changes or nonconforming entities found, 2 = error";
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_BREAKING: i32 = 1;
+pub const EXIT_NONCONFORMING: i32 = 1;
+pub const EXIT_ERROR: i32 = 2;
+
+/// Runs the command given by `args` and returns the exit code of the process.
+/// Used for scripts and CI pipelines, without arguments the desktop app is started instead.
+pub fn run(args: &[String]) -> i32 {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return EXIT_ERROR;
+        }
+    };
+
+    match runtime.block_on(run_command(args)) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            EXIT_ERROR
+        }
+    }
+}
+
+async fn run_command(args: &[String]) -> Result<i32, anyhow::Error> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["diff", repo, model, old_ref, new_ref] => {
+            let diff = ModelDiff::fetch(repo, model, old_ref, new_ref).await?;
+            println!("{}", diff.to_json());
+            Ok(if diff.breaking {
+                EXIT_BREAKING
+            } else {
+                EXIT_OK
+            })
+        }
+        ["broker", "create", file] => {
+            let entity = read_json(Path::new(file))?;
+            Ok(print_exchange(broker()?.create(&entity).await?))
+        }
+        ["broker", "upsert", file] => {
+            let entities = read_json(Path::new(file))?;
+            Ok(print_exchange(broker()?.upsert(&entities).await?))
+        }
+        ["broker", "delete", id] => Ok(print_exchange(broker()?.delete(id).await?)),
+        ["broker", "check"] => check(DEFAULT_REF).await,
+        ["broker", "check", git_ref] => check(git_ref).await,
+        ["csv", mapping, data] => convert_csv(Path::new(mapping), Path::new(data)).await,
+        ["convert", format, file] => convert_entities(format, Path::new(file), DEFAULT_REF).await,
+        ["convert", format, file, git_ref] => {
+            convert_entities(format, Path::new(file), git_ref).await
+        }
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{USAGE}");
+            Ok(EXIT_OK)
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            Ok(EXIT_ERROR)
+        }
+    }
+}
+
+fn broker() -> Result<Broker, anyhow::Error> {
+    let settings = BrokerSettings::load_saved().map_err(|err| {
+        anyhow::anyhow!(
+            "no broker settings at {}, save them in the app first: {err}",
+            settings_path().display()
+        )
+    })?;
+    Ok(Broker::new(settings))
+}
+
+async fn check(git_ref: &str) -> Result<i32, anyhow::Error> {
+    let broker = broker()?;
+    let list = ModelList::fetch(git_ref).await?;
+    let mut types = vec![];
+    for (typ, entities) in broker.entities_by_type().await? {
+        let name = short_name(&typ);
+        let model = match list.repo_of(name) {
+            Some(repo) => {
+                let model_data = ModelData {
+                    repo: repo.name.clone(),
+                    name: name.to_string(),
+                    git_ref: list.git_ref.clone(),
+                };
+                Some(Model::fetch_and_parse(&model_data).await?)
+            }
+            None => None,
+        };
+        types.push(TypeReport::new(&typ, model.as_ref(), &entities));
+    }
+
+    let report = ConformanceReport::new(broker.base_url(), &list.git_ref, types);
+    println!("{}", report.to_json());
+    Ok(if report.conforming {
+        EXIT_OK
+    } else {
+        EXIT_NONCONFORMING
+    })
+}
+
+async fn convert_csv(mapping: &Path, data: &Path) -> Result<i32, anyhow::Error> {
+    let mapping = CsvMapping::load(mapping)?;
+    let table = CsvTable::load(data, mapping.delimiter)?;
+    let model = Model::fetch_and_parse(&mapping.model_data()).await?;
+
+    let mut entities = vec![];
+    let mut code = EXIT_OK;
+    for result in mapping.convert(&model, &table)? {
+        if result.errors.is_empty() {
+            entities.push(result.entity);
+            continue;
+        }
+        code = EXIT_ERROR;
+        for error in result.errors {
+            eprintln!("row {}: {} {}", result.row, error.property, error.message);
+        }
+    }
+    println!("{}", serde_json::to_string_pretty(&entities)?);
+    Ok(code)
+}
+
+async fn convert_entities(format: &str, file: &Path, git_ref: &str) -> Result<i32, anyhow::Error> {
+    let to = Format::from_key(format).ok_or_else(|| {
+        anyhow::anyhow!("unknown format {format}, use normalized, key-values or v2-normalized")
+    })?;
+    let input = read_json(file)?;
+    let list = ModelList::fetch(git_ref).await?;
+    let mut cache = ModelCache::new();
+
+    let entities = match &input {
+        Value::Array(entities) => entities.clone(),
+        entity => vec![entity.clone()],
+    };
+    let mut converted = Vec::with_capacity(entities.len());
+    for entity in entities.iter() {
+        let id = entity["id"].as_str().unwrap_or_default();
+        let name = short_name(entity["type"].as_str().unwrap_or_default());
+        let model = match list.repo_of(name) {
+            Some(repo) => {
+                let model_data = ModelData {
+                    repo: repo.name.clone(),
+                    name: name.to_string(),
+                    git_ref: list.git_ref.clone(),
+                };
+                Some(cache.get_or_fetch_and_insert(&model_data).await?)
+            }
+            None => {
+                eprintln!("{id}: {name} is no smart data model, the kinds are kept or guessed");
+                None
+            }
+        };
+        let (entity, notes) =
+            convert(model.as_ref(), entity, detect(entity), to).map_err(anyhow::Error::msg)?;
+        for note in notes {
+            eprintln!("{id}: {note}");
+        }
+        converted.push(entity);
+    }
+
+    let output = match input {
+        Value::Array(_) => Value::Array(converted),
+        _ => converted.remove(0),
+    };
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(EXIT_OK)
+}
+
+fn read_json(path: &Path) -> Result<Value, anyhow::Error> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn print_exchange(exchange: Exchange) -> i32 {
+    println!("{}", exchange.to_text());
+    if exchange.is_success() {
+        EXIT_OK
+    } else {
+        EXIT_ERROR
+    }
+}
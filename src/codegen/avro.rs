@@ -0,0 +1,260 @@
+use serde_json::{json, Value};
+
+use crate::{
+    codegen::{case::to_pascal_case, one_line, stable_order, types::FieldType, unique_names},
+    smartdata::models::{ParsedModel, Property},
+};
+
+/// Generates an Avro record schema of the entity with all checked properties.
+/// Fields of `previous`, an earlier generated schema, keep their position.
+pub fn generate(model: &ParsedModel, previous: Option<&str>) -> String {
+    let previous: Option<Value> = previous.and_then(|previous| serde_json::from_str(previous).ok());
+    let properties: Vec<_> = model
+        .properties
+        .iter()
+        .filter(|p| p.checked)
+        .cloned()
+        .collect();
+
+    let mut schema = record(
+        &model.name,
+        &model.description,
+        &properties,
+        previous.as_ref(),
+    );
+    if !model.repo.is_empty() {
+        schema["namespace"] = json!(format!("smartdatamodels.{}", avro_name(&model.repo)));
+    }
+
+    let mut avsc = serde_json::to_string_pretty(&schema).unwrap();
+    avsc.push('\n');
+    avsc
+}
+
+fn record(
+    name: &str,
+    description: &str,
+    properties: &[Property],
+    previous: Option<&Value>,
+) -> Value {
+    let previous_names: Vec<String> = previous
+        .and_then(|previous| previous["fields"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|field| Some(field["name"].as_str()?.to_string()))
+        .collect();
+
+    let fields: Vec<_> = stable_order(properties, &previous_names, avro_name)
+        .into_iter()
+        .map(|property| {
+            let name = avro_name(&property.name);
+            let previous_type = previous
+                .and_then(|previous| previous["fields"].as_array())
+                .into_iter()
+                .flatten()
+                .find(|field| field["name"].as_str() == Some(name.as_str()))
+                .map(|field| &field["type"]);
+            field(property, previous_type)
+        })
+        .collect();
+
+    let mut record = json!({ "type": "record", "name": avro_name(name) });
+    let description = one_line(description);
+    if !description.is_empty() {
+        record["doc"] = json!(description);
+    }
+    record["fields"] = json!(fields);
+    record
+}
+
+fn field(property: &Property, previous: Option<&Value>) -> Value {
+    let mut field = json!({ "name": avro_name(&property.name) });
+
+    let mut doc = one_line(property.description.as_deref().unwrap_or_default());
+    if let Some(units) = property.units() {
+        doc = format!("{doc} Units: {units}").trim().to_string();
+    }
+    if !doc.is_empty() {
+        field["doc"] = json!(doc);
+    }
+
+    let typ = avro_type(property, previous.and_then(nested_record));
+    if property.required {
+        field["type"] = typ;
+    } else {
+        // Optional fields are unions with null, the default keeps old readers compatible
+        field["type"] = json!(["null", typ]);
+        field["default"] = Value::Null;
+    }
+    field
+}
+
+fn avro_type(property: &Property, previous: Option<&Value>) -> Value {
+    match FieldType::from_property(property) {
+        FieldType::String | FieldType::Uri | FieldType::Relationship(_) => json!("string"),
+        FieldType::Integer => json!("long"),
+        FieldType::Number => json!("double"),
+        FieldType::Boolean => json!("boolean"),
+        FieldType::DateTime => json!({ "type": "long", "logicalType": "timestamp-millis" }),
+        FieldType::Date => json!({ "type": "int", "logicalType": "date" }),
+        FieldType::Time => json!({ "type": "int", "logicalType": "time-millis" }),
+        FieldType::Enum(values) => {
+            let symbols = unique_names(values.iter().map(|value| avro_name(value)));
+            json!({ "type": "enum", "name": to_pascal_case(&property.name), "symbols": symbols })
+        }
+        // Avro has no type for arbitrary json, GeoJSON is kept as its serialized form
+        FieldType::Geo(_) => json!("string"),
+        FieldType::Object => {
+            let members = property.members();
+            if members.is_empty() {
+                json!("string")
+            } else {
+                let description = property.description.as_deref().unwrap_or_default();
+                record(
+                    &to_pascal_case(&property.name),
+                    description,
+                    &members,
+                    previous,
+                )
+            }
+        }
+        FieldType::Array(_) => {
+            let item = Property {
+                name: format!("{}Item", property.name),
+                ..property.item().unwrap_or_default()
+            };
+            json!({ "type": "array", "items": avro_type(&item, previous) })
+        }
+    }
+}
+
+/// Finds the record of a previous field type, which may be wrapped in a union or an array.
+fn nested_record(typ: &Value) -> Option<&Value> {
+    match typ {
+        Value::Array(union) => union.iter().find_map(nested_record),
+        Value::Object(object) => match object.get("type").and_then(Value::as_str) {
+            Some("record") => Some(typ),
+            Some("array") => nested_record(&typ["items"]),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Avro names may only contain letters, digits and underscores and must not start with a digit.
+fn avro_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::generate;
+
+    #[test]
+    fn optional_unions_and_nested_records() {
+        let model = ParsedModel {
+            name: "Building".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml(
+                    "category",
+                    "{type: string, enum: [office, non-residential, non_residential]}",
+                ),
+                Property::from_yaml(
+                    "address",
+                    "{type: object, required: [addressLocality], properties: {addressLocality: {type: string}}}",
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let schema: Value = serde_json::from_str(&generate(&model, None)).unwrap();
+        let fields = &schema["fields"];
+
+        assert_eq!(fields[0]["type"], "string");
+        assert_eq!(fields[1]["type"][0], "null");
+        assert_eq!(
+            fields[1]["type"][1]["symbols"],
+            json!(["office", "non_residential", "non_residential_2"])
+        );
+        assert_eq!(fields[2]["type"][1]["type"], "record");
+        assert_eq!(
+            fields[2]["type"][1]["fields"][0]["type"], "string",
+            "required members are no unions"
+        );
+    }
+
+    #[test]
+    fn keep_previous_order() {
+        let model = |properties: Vec<Property>| ParsedModel {
+            name: "Building".to_string(),
+            properties,
+            ..Default::default()
+        };
+        let previous = generate(
+            &model(vec![
+                Property::from_yaml("name", "{type: string}"),
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml(
+                    "fill-level",
+                    "{type: object, properties: {value: {type: number}, unit: {type: string}}}",
+                ),
+            ]),
+            None,
+        );
+
+        let schema = generate(
+            &model(vec![
+                Property::from_yaml(
+                    "fill-level",
+                    "{type: object, properties: {unit: {type: string}, value: {type: number}}}",
+                ),
+                Property::from_yaml("floors", "{type: integer}"),
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml("name", "{type: string}"),
+            ]),
+            Some(&previous),
+        );
+        let schema: Value = serde_json::from_str(&schema).unwrap();
+        let names: Vec<_> = schema["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["name"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(names, vec!["name", "id", "fill_level", "floors"]);
+        let members: Vec<_> = schema["fields"][2]["type"][1]["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            members,
+            vec!["value", "unit"],
+            "sanitized names keep their nested order"
+        );
+    }
+}
@@ -0,0 +1,92 @@
+/// Splits camelCase, PascalCase, snake_case and kebab-case names into lowercase words.
+pub fn words(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut current = String::new();
+    let chars: Vec<char> = name.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        // A new word starts at `aB` and at the last upper case char of `ABc`
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let starts_word = c.is_uppercase()
+            && (prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(char::is_uppercase)
+                    && next.is_some_and(|n| n.is_lowercase())));
+        if starts_word && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+pub fn to_snake_case(name: &str) -> String {
+    words(name).join("_")
+}
+
+pub fn to_screaming_snake_case(name: &str) -> String {
+    to_snake_case(name).to_uppercase()
+}
+
+pub fn to_kebab_case(name: &str) -> String {
+    words(name).join("-")
+}
+
+pub fn to_camel_case(name: &str) -> String {
+    let pascal = to_pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn to_pascal_case(name: &str) -> String {
+    words(name).iter().map(|word| capitalize(word)).collect()
+}
+
+/// A label like `Battery level` for `batteryLevel`.
+pub fn to_sentence_case(name: &str) -> String {
+    capitalize(&words(name).join(" "))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        to_camel_case, to_kebab_case, to_pascal_case, to_screaming_snake_case, to_sentence_case,
+        to_snake_case,
+    };
+
+    #[test]
+    fn convert_cases() {
+        assert_eq!(to_snake_case("WeatherObserved"), "weather_observed");
+        assert_eq!(to_snake_case("refDeviceModel"), "ref_device_model");
+        assert_eq!(to_snake_case("CO2Level"), "co2_level");
+        assert_eq!(to_snake_case("non-free"), "non_free");
+        assert_eq!(to_pascal_case("date_observed"), "DateObserved");
+        assert_eq!(to_pascal_case("typeOfLocation"), "TypeOfLocation");
+        assert_eq!(to_screaming_snake_case("notOccupied"), "NOT_OCCUPIED");
+        assert_eq!(to_camel_case("AirQualityObserved"), "airQualityObserved");
+        assert_eq!(to_kebab_case("AirQualityObserved"), "air-quality-observed");
+        assert_eq!(to_sentence_case("refDeviceModel"), "Ref device model");
+    }
+}
@@ -0,0 +1,249 @@
+//! A language neutral class model of an entity, which the generators of class based languages
+//! render. All type decisions are made here, so Java, Kotlin and C# agree on them.
+
+use crate::{
+    codegen::{
+        case::{to_camel_case, to_pascal_case, to_screaming_snake_case},
+        one_line,
+        types::FieldType,
+        unique_names,
+    },
+    smartdata::models::{ParsedModel, Property},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeRef {
+    /// Also used for uris and relationships, which are not always valid urls.
+    String,
+    Integer,
+    Number,
+    Boolean,
+    DateTime,
+    Date,
+    Time,
+    /// A json value without a fixed shape, like GeoJSON geometries or free objects.
+    Json,
+    /// An enum or class declared next to the entity.
+    Named(String),
+    List(Box<TypeRef>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Class {
+    pub name: String,
+    pub description: String,
+    pub fields: Vec<Field>,
+    /// Enums and classes of the fields, with their own nested declarations flattened into these.
+    pub enums: Vec<Enum>,
+    pub classes: Vec<Class>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    /// The name in json.
+    pub name: String,
+    /// The name as an identifier in camelCase, languages may still have to escape keywords.
+    pub ident: String,
+    pub typ: TypeRef,
+    pub required: bool,
+    /// The description with the units appended.
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    pub name: String,
+    pub constants: Vec<EnumConstant>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumConstant {
+    pub value: String,
+    /// `OUT_OF_SERVICE` for `outOfService`.
+    pub screaming: String,
+    /// `OutOfService` for `outOfService`.
+    pub pascal: String,
+}
+
+impl Class {
+    /// The class of the checked properties of `model`.
+    pub fn new(model: &ParsedModel) -> Self {
+        let properties: Vec<_> = model
+            .properties
+            .iter()
+            .filter(|p| p.checked)
+            .cloned()
+            .collect();
+        Class::from_properties(&model.name, &model.description, &properties)
+    }
+
+    fn from_properties(name: &str, description: &str, properties: &[Property]) -> Self {
+        let mut class = Class {
+            name: name.to_string(),
+            description: one_line(description),
+            fields: vec![],
+            enums: vec![],
+            classes: vec![],
+        };
+
+        for property in properties {
+            let mut description = one_line(property.description.as_deref().unwrap_or_default());
+            if let Some(units) = property.units() {
+                description = format!("{description} Units: {units}").trim().to_string();
+            }
+            let field = FieldType::from_property(property);
+            let typ = class.type_ref(property, &field);
+
+            class.fields.push(Field {
+                name: property.name.clone(),
+                ident: to_camel_case(&property.name),
+                typ,
+                required: property.required,
+                description,
+            });
+        }
+        class
+    }
+
+    /// Maps the type of a property, enums and classes are declared on the way.
+    /// Nested types are prefixed with the entity name, so they are unique in a file.
+    fn type_ref(&mut self, property: &Property, field: &FieldType) -> TypeRef {
+        match field {
+            FieldType::String | FieldType::Uri | FieldType::Relationship(_) => TypeRef::String,
+            FieldType::Integer => TypeRef::Integer,
+            FieldType::Number => TypeRef::Number,
+            FieldType::Boolean => TypeRef::Boolean,
+            FieldType::DateTime => TypeRef::DateTime,
+            FieldType::Date => TypeRef::Date,
+            FieldType::Time => TypeRef::Time,
+            FieldType::Geo(_) => TypeRef::Json,
+            FieldType::Enum(values) => {
+                let name = format!("{}{}", self.name, to_pascal_case(&property.name));
+                self.enums.push(Enum::new(&name, values));
+                TypeRef::Named(name)
+            }
+            FieldType::Object => {
+                let members = property.members();
+                if members.is_empty() {
+                    return TypeRef::Json;
+                }
+                let name = format!("{}{}", self.name, to_pascal_case(&property.name));
+                let description = property.description.as_deref().unwrap_or_default();
+                let mut class = Class::from_properties(&name, description, &members);
+                self.enums.append(&mut class.enums);
+                self.classes.append(&mut class.classes);
+                self.classes.push(class);
+                TypeRef::Named(name)
+            }
+            FieldType::Array(item) => {
+                let item_property = Property {
+                    name: property.name.clone(),
+                    ..property.item().unwrap_or_default()
+                };
+                TypeRef::List(Box::new(self.type_ref(&item_property, item)))
+            }
+        }
+    }
+
+    /// Whether a field of this class or of a nested class uses a type matching `predicate`.
+    pub fn uses(&self, predicate: impl Fn(&TypeRef) -> bool + Copy) -> bool {
+        fn matches(typ: &TypeRef, predicate: impl Fn(&TypeRef) -> bool) -> bool {
+            match typ {
+                TypeRef::List(item) => predicate(typ) || matches(item, predicate),
+                _ => predicate(typ),
+            }
+        }
+
+        self.fields
+            .iter()
+            .any(|field| matches(&field.typ, predicate))
+            || self.classes.iter().any(|class| class.uses(predicate))
+    }
+}
+
+impl Enum {
+    fn new(name: &str, values: &[String]) -> Self {
+        let mut screaming = vec![];
+        let mut pascal = vec![];
+        for (i, value) in values.iter().enumerate() {
+            let value_screaming = to_screaming_snake_case(value);
+            let value_pascal = to_pascal_case(value);
+            // Identifiers must not be empty or start with a digit
+            if value_screaming.is_empty()
+                || value_screaming.starts_with(|c: char| c.is_ascii_digit())
+            {
+                screaming.push(
+                    format!("VALUE_{i}_{value_screaming}")
+                        .trim_end_matches('_')
+                        .to_string(),
+                );
+                pascal.push(format!("Value{i}{value_pascal}"));
+            } else {
+                screaming.push(value_screaming);
+                pascal.push(value_pascal);
+            }
+        }
+
+        let constants = values
+            .iter()
+            .zip(unique_names(screaming))
+            .zip(unique_names(pascal))
+            .map(|((value, screaming), pascal)| EnumConstant {
+                value: value.clone(),
+                screaming,
+                pascal,
+            })
+            .collect();
+        Enum {
+            name: name.to_string(),
+            constants,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::{Class, TypeRef};
+
+    #[test]
+    fn neutral_types() {
+        let model = ParsedModel {
+            name: "Building".to_string(),
+            properties: vec![
+                Property::from_yaml("refOwner", "{type: string, format: uri}"),
+                Property::from_yaml(
+                    "category",
+                    "{type: array, items: {type: string, enum: [office, 3D, on-street, onStreet]}}",
+                ),
+                Property::from_yaml(
+                    "address",
+                    "{type: object, properties: {streetAddress: {type: string}}}",
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let class = Class::new(&model);
+
+        assert_eq!(class.fields[0].typ, TypeRef::String);
+        assert_eq!(
+            class.fields[1].typ,
+            TypeRef::List(Box::new(TypeRef::Named("BuildingCategory".to_string())))
+        );
+        assert_eq!(class.enums[0].constants[1].screaming, "VALUE_1_3_D");
+        let constants = &class.enums[0].constants;
+        assert_eq!(constants.len(), 4, "colliding values are kept");
+        assert_eq!(
+            (
+                constants[3].screaming.as_str(),
+                constants[3].pascal.as_str()
+            ),
+            ("ON_STREET_2", "OnStreet_2")
+        );
+        assert_eq!(class.classes[0].name, "BuildingAddress");
+        assert!(class.uses(|typ| matches!(typ, TypeRef::List(_))));
+        assert!(!class.uses(|typ| *typ == TypeRef::DateTime));
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt::Write;
+
+use crate::{
+    codegen::{
+        case::to_pascal_case,
+        classes::{Class, Enum, TypeRef},
+    },
+    smartdata::models::ParsedModel,
+};
+
+const NAMESPACE: &str = "SmartDataModels";
+
+/// Generates C# records for System.Text.Json, nested types follow the entity in the namespace.
+pub fn generate(model: &ParsedModel) -> String {
+    let class = Class::new(model);
+
+    let mut usings = vec![];
+    if class.uses(|typ| matches!(typ, TypeRef::List(_))) {
+        usings.push("System.Collections.Generic");
+    }
+    if class.uses(|typ| *typ == TypeRef::Json) {
+        usings.push("System.Text.Json");
+    }
+    usings.push("System.Text.Json.Serialization");
+    if class.uses(|typ| matches!(typ, TypeRef::DateTime | TypeRef::Date | TypeRef::Time)) {
+        usings.insert(0, "System");
+    }
+
+    let mut cs = String::new();
+    writeln!(cs, "// Generated from the smart data model {}.", model.name).unwrap();
+    writeln!(cs).unwrap();
+    for using in usings {
+        writeln!(cs, "using {using};").unwrap();
+    }
+    writeln!(cs).unwrap();
+    writeln!(cs, "namespace {NAMESPACE};").unwrap();
+
+    writeln!(cs).unwrap();
+    write_record(&mut cs, &class);
+    for e in class.enums.iter() {
+        writeln!(cs).unwrap();
+        write_enum(&mut cs, e);
+    }
+    for nested in class.classes.iter() {
+        writeln!(cs).unwrap();
+        write_record(&mut cs, nested);
+    }
+    cs
+}
+
+fn write_record(cs: &mut String, class: &Class) {
+    write_summary(cs, &class.description, "");
+    writeln!(cs, "public record {}", class.name).unwrap();
+    writeln!(cs, "{{").unwrap();
+    for (i, field) in class.fields.iter().enumerate() {
+        if i > 0 {
+            writeln!(cs).unwrap();
+        }
+        write_summary(cs, &field.description, "    ");
+        writeln!(cs, "    [JsonPropertyName(\"{}\")]", field.name).unwrap();
+
+        // Members must not be named like their record
+        let mut name = to_pascal_case(&field.name);
+        if name == class.name || name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            name = format!("Value{name}");
+        }
+        let typ = cs_type(&field.typ);
+        if field.required {
+            writeln!(cs, "    public required {typ} {name} {{ get; init; }}").unwrap();
+        } else {
+            writeln!(
+                cs,
+                "    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]"
+            )
+            .unwrap();
+            writeln!(cs, "    public {typ}? {name} {{ get; init; }}").unwrap();
+        }
+    }
+    writeln!(cs, "}}").unwrap();
+}
+
+fn write_enum(cs: &mut String, e: &Enum) {
+    writeln!(
+        cs,
+        "[JsonConverter(typeof(JsonStringEnumConverter<{}>))]",
+        e.name
+    )
+    .unwrap();
+    writeln!(cs, "public enum {}", e.name).unwrap();
+    writeln!(cs, "{{").unwrap();
+    for constant in e.constants.iter() {
+        writeln!(cs, "    [JsonStringEnumMemberName(\"{}\")]", constant.value).unwrap();
+        writeln!(cs, "    {},", constant.pascal).unwrap();
+    }
+    writeln!(cs, "}}").unwrap();
+}
+
+fn write_summary(cs: &mut String, text: &str, indent: &str) {
+    if text.is_empty() {
+        return;
+    }
+    let text = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    writeln!(cs, "{indent}/// <summary>{text}</summary>").unwrap();
+}
+
+fn cs_type(typ: &TypeRef) -> String {
+    match typ {
+        TypeRef::String => String::from("string"),
+        TypeRef::Integer => String::from("long"),
+        TypeRef::Number => String::from("double"),
+        TypeRef::Boolean => String::from("bool"),
+        TypeRef::DateTime => String::from("DateTimeOffset"),
+        TypeRef::Date => String::from("DateOnly"),
+        TypeRef::Time => String::from("TimeOnly"),
+        TypeRef::Json => String::from("JsonElement"),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::List(item) => format!("List<{}>", cs_type(item)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::generate;
+
+    #[test]
+    fn create_records() {
+        let cs = "// Generated from the smart data model Device.\n\n\
+            using System.Text.Json.Serialization;\n\n\
+            namespace SmartDataModels;\n\n\
+            public record Device\n\
+            {\n    \
+            [JsonPropertyName(\"id\")]\n    \
+            public required string Id { get; init; }\n\n    \
+            /// <summary>Battery level. Units: percent</summary>\n    \
+            [JsonPropertyName(\"batteryLevel\")]\n    \
+            [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]\n    \
+            public double? BatteryLevel { get; init; }\n\n    \
+            [JsonPropertyName(\"address\")]\n    \
+            [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]\n    \
+            public DeviceAddress? Address { get; init; }\n\
+            }\n\n\
+            [JsonConverter(typeof(JsonStringEnumConverter<DeviceAddressKind>))]\n\
+            public enum DeviceAddressKind\n\
+            {\n    \
+            [JsonStringEnumMemberName(\"home\")]\n    \
+            Home,\n    \
+            [JsonStringEnumMemberName(\"work-place\")]\n    \
+            WorkPlace,\n\
+            }\n\n\
+            public record DeviceAddress\n\
+            {\n    \
+            [JsonPropertyName(\"kind\")]\n    \
+            [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]\n    \
+            public DeviceAddressKind? Kind { get; init; }\n\
+            }\n";
+
+        let model = ParsedModel {
+            name: "Device".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml(
+                    "batteryLevel",
+                    "{type: number, description: Battery level., x-ngsi: {units: percent}}",
+                ),
+                Property::from_yaml(
+                    "address",
+                    "{type: object, properties: {kind: {type: string, enum: [home, work-place]}}}",
+                ),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(cs, generate(&model));
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt::Write;
+
+use crate::{
+    codegen::{
+        case::{to_pascal_case, words},
+        one_line,
+        types::FieldType,
+        unique_names,
+    },
+    smartdata::models::{ParsedModel, Property},
+};
+
+/// Words Go spells in upper case in identifiers.
+const INITIALISMS: [&str; 6] = ["id", "url", "uri", "api", "json", "http"];
+
+/// Generates Go structs of the entity with all checked properties.
+pub fn generate(model: &ParsedModel) -> String {
+    let properties: Vec<_> = model
+        .properties
+        .iter()
+        .filter(|p| p.checked)
+        .cloned()
+        .collect();
+
+    let mut output = Output::default();
+    output.write_struct(&model.name, &model.description, &properties);
+
+    let package: String = model
+        .repo
+        .trim_start_matches("dataModel.")
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_lowercase();
+    let package = if package.is_empty() {
+        String::from("models")
+    } else {
+        package
+    };
+
+    let mut go = String::new();
+    writeln!(
+        go,
+        "// Code generated from the smart data model {}. DO NOT EDIT.",
+        model.name
+    )
+    .unwrap();
+    writeln!(go).unwrap();
+    writeln!(go, "package {package}").unwrap();
+    writeln!(go).unwrap();
+    if output.uses_time {
+        writeln!(go, "import \"time\"").unwrap();
+        writeln!(go).unwrap();
+    }
+    if output.uses_geometry {
+        writeln!(go, "// Geometry is a GeoJSON geometry.").unwrap();
+        writeln!(go, "type Geometry struct {{").unwrap();
+        writeln!(go, "\tType        string `json:\"type\"`").unwrap();
+        writeln!(go, "\tCoordinates any    `json:\"coordinates\"`").unwrap();
+        writeln!(go, "}}").unwrap();
+        writeln!(go).unwrap();
+    }
+    go.push_str(output.declarations.trim_end());
+    go.push('\n');
+    go
+}
+
+/// Collects the type declarations, nested structs and enums come after the struct using them.
+#[derive(Default)]
+struct Output {
+    declarations: String,
+    uses_time: bool,
+    uses_geometry: bool,
+}
+
+impl Output {
+    fn write_struct(&mut self, name: &str, description: &str, properties: &[Property]) {
+        let mut nested = vec![];
+        let fields: Vec<_> = properties
+            .iter()
+            .map(|property| {
+                let typ = self.go_type(name, property, &mut nested);
+                let tag = if property.required {
+                    format!("`json:\"{}\"`", property.name)
+                } else {
+                    format!("`json:\"{},omitempty\"`", property.name)
+                };
+                (property, go_name(&property.name), typ, tag)
+            })
+            .collect();
+
+        // Aligned like gofmt does
+        let name_width = fields.iter().map(|(_, n, _, _)| n.len()).max().unwrap_or(0);
+        let type_width = fields.iter().map(|(_, _, t, _)| t.len()).max().unwrap_or(0);
+
+        let mut go = String::new();
+        write_comment(&mut go, name, description);
+        writeln!(go, "type {name} struct {{").unwrap();
+        for (property, field_name, typ, tag) in fields.iter() {
+            let mut description = one_line(property.description.as_deref().unwrap_or_default());
+            if let Some(units) = property.units() {
+                write!(description, " Units: {units}").unwrap();
+            }
+            if !description.trim().is_empty() {
+                writeln!(go, "\t// {}", description.trim()).unwrap();
+            }
+            writeln!(go, "\t{field_name:name_width$} {typ:type_width$} {tag}").unwrap();
+        }
+        writeln!(go, "}}").unwrap();
+        writeln!(go).unwrap();
+
+        self.declarations.push_str(&go);
+        for declaration in nested {
+            self.declarations.push_str(&declaration);
+        }
+    }
+
+    /// The type of a field, types which have to be declared are added to `nested`.
+    fn go_type(&mut self, parent: &str, property: &Property, nested: &mut Vec<String>) -> String {
+        let field = FieldType::from_property(property);
+        let typ = self.value_type(parent, property, &field, nested);
+
+        // Optional values are pointers, so a missing value differs from the zero value
+        let is_pointer = match field {
+            FieldType::Array(_) => false,
+            FieldType::Object => !property.required && !typ.starts_with("map["),
+            _ => !property.required,
+        };
+        if is_pointer {
+            format!("*{typ}")
+        } else {
+            typ
+        }
+    }
+
+    fn value_type(
+        &mut self,
+        parent: &str,
+        property: &Property,
+        field: &FieldType,
+        nested: &mut Vec<String>,
+    ) -> String {
+        match field {
+            FieldType::String
+            | FieldType::Uri
+            | FieldType::Relationship(_)
+            | FieldType::Date
+            | FieldType::Time => String::from("string"),
+            FieldType::Integer => String::from("int64"),
+            FieldType::Number => String::from("float64"),
+            FieldType::Boolean => String::from("bool"),
+            FieldType::DateTime => {
+                self.uses_time = true;
+                String::from("time.Time")
+            }
+            FieldType::Enum(values) => {
+                let name = format!("{parent}{}", to_pascal_case(&property.name));
+                nested.push(enum_declaration(&name, values));
+                name
+            }
+            FieldType::Geo(_) => {
+                self.uses_geometry = true;
+                String::from("Geometry")
+            }
+            FieldType::Object => {
+                let members = property.members();
+                if members.is_empty() {
+                    return String::from("map[string]any");
+                }
+                let name = format!("{parent}{}", to_pascal_case(&property.name));
+                let mut output = Output::default();
+                output.write_struct(
+                    &name,
+                    property.description.as_deref().unwrap_or_default(),
+                    &members,
+                );
+                self.uses_time |= output.uses_time;
+                self.uses_geometry |= output.uses_geometry;
+                nested.push(output.declarations);
+                name
+            }
+            FieldType::Array(item) => {
+                let item_property = Property {
+                    name: property.name.clone(),
+                    ..property.item().unwrap_or_default()
+                };
+                format!(
+                    "[]{}",
+                    self.value_type(parent, &item_property, item, nested)
+                )
+            }
+        }
+    }
+}
+
+fn enum_declaration(name: &str, values: &[String]) -> String {
+    let names = values.iter().enumerate().map(|(i, value)| {
+        let suffix = to_pascal_case(value);
+        let suffix = if suffix.is_empty() {
+            format!("Value{i}")
+        } else {
+            suffix
+        };
+        format!("{name}{suffix}")
+    });
+    let constants: Vec<_> = unique_names(names).into_iter().zip(values).collect();
+    let width = constants.iter().map(|(c, _)| c.len()).max().unwrap_or(0);
+
+    let mut go = String::new();
+    writeln!(go, "// {name} is one of the allowed values.").unwrap();
+    writeln!(go, "type {name} string").unwrap();
+    writeln!(go).unwrap();
+    writeln!(go, "const (").unwrap();
+    for (constant, value) in constants {
+        writeln!(
+            go,
+            "\t{constant:width$} {name} = {}",
+            serde_json::to_string(value).unwrap()
+        )
+        .unwrap();
+    }
+    writeln!(go, ")").unwrap();
+    writeln!(go).unwrap();
+    go
+}
+
+/// Go doc comments start with the name they describe.
+fn write_comment(go: &mut String, name: &str, description: &str) {
+    let description = one_line(description);
+    if !description.is_empty() {
+        writeln!(go, "// {name} {description}").unwrap();
+    }
+}
+
+/// Exported Go identifiers, e.g. `refDeviceId` becomes `RefDeviceID`.
+fn go_name(name: &str) -> String {
+    let name: String = words(name)
+        .iter()
+        .map(|word| {
+            if INITIALISMS.contains(&word.as_str()) {
+                word.to_uppercase()
+            } else {
+                to_pascal_case(word)
+            }
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("X{name}")
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property, XNgsi};
+
+    use super::generate;
+
+    #[test]
+    fn create_structs() {
+        let go = "// Code generated from the smart data model Device. DO NOT EDIT.\n\n\
+            package models\n\n\
+            import \"time\"\n\n\
+            // Geometry is a GeoJSON geometry.\n\
+            type Geometry struct {\n\
+            \tType        string `json:\"type\"`\n\
+            \tCoordinates any    `json:\"coordinates\"`\n\
+            }\n\n\
+            type Device struct {\n\
+            \tID           string        `json:\"id\"`\n\
+            \tBatteryLevel *float64      `json:\"batteryLevel,omitempty\"`\n\
+            \tDateCreated  *time.Time    `json:\"dateCreated,omitempty\"`\n\
+            \tLocation     *Geometry     `json:\"location,omitempty\"`\n\
+            \tStatus       *DeviceStatus `json:\"status,omitempty\"`\n\
+            \tValues       []float64     `json:\"values,omitempty\"`\n\
+            }\n\n\
+            // DeviceStatus is one of the allowed values.\n\
+            type DeviceStatus string\n\n\
+            const (\n\
+            \tDeviceStatusOk             DeviceStatus = \"ok\"\n\
+            \tDeviceStatusOutOfService   DeviceStatus = \"outOfService\"\n\
+            \tDeviceStatusOutOfService_2 DeviceStatus = \"out-of-service\"\n\
+            )\n";
+
+        let model = ParsedModel {
+            name: "Device".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml("batteryLevel", "{type: number}"),
+                Property::from_yaml("dateCreated", "{type: string, format: date-time}"),
+                Property {
+                    xngsi: Some(XNgsi {
+                        typ: Some("GeoProperty".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Property::from_yaml("location", "{type: object}")
+                },
+                Property::from_yaml(
+                    "status",
+                    "{type: string, enum: [ok, outOfService, out-of-service]}",
+                ),
+                Property::from_yaml("values", "{type: array, items: {type: number}}"),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(go, generate(&model));
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt::Write;
+
+use crate::{
+    codegen::{
+        case::{to_pascal_case, to_screaming_snake_case},
+        one_line,
+        types::FieldType,
+        unique_names,
+    },
+    smartdata::models::{ParsedModel, Property},
+};
+
+/// Custom scalars, declared only if a field uses them.
+const SCALARS: [&str; 4] = ["DateTime", "URI", "GeoJSON", "JSON"];
+
+/// Generates a GraphQL SDL object type of the entity with all checked properties.
+pub fn generate(model: &ParsedModel) -> String {
+    let properties: Vec<_> = model.properties.iter().filter(|p| p.checked).collect();
+    let mut enums = vec![];
+    let mut fields = vec![];
+    for property in properties {
+        let field = FieldType::from_property(property);
+        let typ = graphql_type(model, property, &field);
+        if let Some(values) = enum_values(&field) {
+            let name = typ.trim_matches(['[', ']']).to_string();
+            enums.push((name, property, values.clone()));
+        }
+        fields.push((property, typ));
+    }
+
+    let mut sdl = String::new();
+    write_description(&mut sdl, "", &model.description);
+    writeln!(sdl, "type {} {{", model.name).unwrap();
+    let names = unique_names(
+        fields
+            .iter()
+            .map(|(property, _)| field_name(&property.name)),
+    );
+    for ((property, typ), name) in fields.iter().zip(names) {
+        let mut description = one_line(property.description.as_deref().unwrap_or_default());
+        if let Some(units) = property.units() {
+            write!(description, " Units: {units}").unwrap();
+        }
+        // Renamed fields keep the attribute name in the docs, like enum values
+        if name != property.name {
+            write!(description, " Attribute: {}", property.name).unwrap();
+        }
+        write_description(&mut sdl, "  ", &description);
+        let required = if property.required { "!" } else { "" };
+        writeln!(sdl, "  {name}: {typ}{required}").unwrap();
+    }
+    writeln!(sdl, "}}").unwrap();
+
+    for (name, property, values) in enums {
+        writeln!(sdl).unwrap();
+        write_description(
+            &mut sdl,
+            "",
+            &format!("Values of {}.{}", model.name, property.name),
+        );
+        writeln!(sdl, "enum {name} {{").unwrap();
+        let variants = unique_names(values.iter().map(|value| enum_value(value)));
+        for (value, variant) in values.iter().zip(variants) {
+            // Values which are no valid GraphQL names keep their original spelling in the docs
+            if variant.to_lowercase() != *value {
+                write_description(&mut sdl, "  ", value);
+            }
+            writeln!(sdl, "  {variant}").unwrap();
+        }
+        writeln!(sdl, "}}").unwrap();
+    }
+
+    // Related entities are only known by their id, unless their models are generated as well
+    let mut targets: Vec<_> = fields
+        .iter()
+        .filter_map(|(property, _)| relationship_target(&FieldType::from_property(property)))
+        .filter(|target| *target != model.name)
+        .collect();
+    targets.sort();
+    targets.dedup();
+    for target in targets {
+        writeln!(sdl).unwrap();
+        write_description(
+            &mut sdl,
+            "",
+            &format!("Stub of {target}, replace it by the type generated from its model."),
+        );
+        writeln!(sdl, "type {target} {{\n  id: ID!\n}}").unwrap();
+    }
+
+    let used: Vec<_> = SCALARS
+        .into_iter()
+        .filter(|scalar| {
+            fields
+                .iter()
+                .any(|(_, typ)| typ.trim_matches(['[', ']']) == *scalar)
+        })
+        .collect();
+    if !used.is_empty() {
+        writeln!(sdl).unwrap();
+    }
+    for scalar in used {
+        writeln!(sdl, "scalar {scalar}").unwrap();
+    }
+    sdl
+}
+
+fn graphql_type(model: &ParsedModel, property: &Property, field: &FieldType) -> String {
+    if property.name == "id" {
+        return String::from("ID");
+    }
+
+    match field {
+        FieldType::String | FieldType::Date | FieldType::Time => String::from("String"),
+        FieldType::Integer => String::from("Int"),
+        FieldType::Number => String::from("Float"),
+        FieldType::Boolean => String::from("Boolean"),
+        FieldType::DateTime => String::from("DateTime"),
+        FieldType::Uri => String::from("URI"),
+        FieldType::Enum(_) => format!("{}{}", model.name, to_pascal_case(&property.name)),
+        FieldType::Geo(_) => String::from("GeoJSON"),
+        FieldType::Relationship(target) => target.clone(),
+        FieldType::Object => String::from("JSON"),
+        FieldType::Array(item) => format!("[{}]", graphql_type(model, property, item)),
+    }
+}
+
+/// The values of enums and arrays of enums.
+fn enum_values(field: &FieldType) -> Option<&Vec<String>> {
+    match field {
+        FieldType::Enum(values) => Some(values),
+        FieldType::Array(item) => enum_values(item),
+        _ => None,
+    }
+}
+
+/// The target type of relationships and arrays of relationships.
+fn relationship_target(field: &FieldType) -> Option<String> {
+    match field {
+        FieldType::Relationship(target) => Some(target.clone()),
+        FieldType::Array(item) => relationship_target(item),
+        _ => None,
+    }
+}
+
+/// Field names have to be GraphQL names, e.g. `fill-level` becomes `fill_level`.
+fn field_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+/// Enum values have to be GraphQL names, e.g. `not occupied` becomes `NOT_OCCUPIED`.
+fn enum_value(value: &str) -> String {
+    let name = to_screaming_snake_case(value);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name
+    }
+}
+
+/// Writes a block string, which GraphQL tools show as documentation.
+fn write_description(sdl: &mut String, indent: &str, description: &str) {
+    let description = one_line(description);
+    if description.is_empty() {
+        return;
+    }
+    writeln!(
+        sdl,
+        "{indent}\"\"\"\n{indent}{}\n{indent}\"\"\"",
+        description.replace("\"\"\"", "\\\"\"\"")
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property, XNgsi};
+
+    use super::generate;
+
+    #[test]
+    fn create_type() {
+        let sdl = "type ParkingSpot {\n  \
+            id: ID!\n  \
+            \"\"\"\n  Width of the spot. Units: MTR\n  \"\"\"\n  \
+            width: Float\n  \
+            status: ParkingSpotStatus!\n  \
+            refParkingSite: ParkingSite\n  \
+            dateObserved: DateTime\n\
+            }\n\n\
+            \"\"\"\nValues of ParkingSpot.status\n\"\"\"\n\
+            enum ParkingSpotStatus {\n  \
+            FREE\n  \
+            \"\"\"\n  not occupied\n  \"\"\"\n  \
+            NOT_OCCUPIED\n  \
+            \"\"\"\n  not-occupied\n  \"\"\"\n  \
+            NOT_OCCUPIED_2\n\
+            }\n\n\
+            \"\"\"\nStub of ParkingSite, replace it by the type generated from its model.\n\"\"\"\n\
+            type ParkingSite {\n  id: ID!\n}\n\n\
+            scalar DateTime\n";
+
+        let model = ParsedModel {
+            name: "ParkingSpot".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property {
+                    xngsi: Some(XNgsi {
+                        units: Some("MTR".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Property::from_yaml(
+                        "width",
+                        "{type: number, description: Width of the spot.}",
+                    )
+                },
+                Property {
+                    required: true,
+                    ..Property::from_yaml(
+                        "status",
+                        "{type: string, enum: [free, not occupied, not-occupied]}",
+                    )
+                },
+                Property::from_yaml("refParkingSite", "{type: string, format: uri}"),
+                Property::from_yaml("dateObserved", "{type: string, format: date-time}"),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(sdl, generate(&model));
+    }
+
+    #[test]
+    fn sanitize_field_names() {
+        let model = ParsedModel {
+            name: "WasteContainer".to_string(),
+            properties: vec![
+                Property::from_yaml("fill-level", "{type: number}"),
+                Property::from_yaml("fill_level", "{type: number}"),
+                Property::from_yaml("3dModel", "{type: string}"),
+                Property::from_yaml("serial.number", "{type: string}"),
+            ],
+            ..Default::default()
+        };
+
+        let sdl = generate(&model);
+        assert!(sdl.contains("  \"\"\"\n  Attribute: fill-level\n  \"\"\"\n  fill_level: Float\n"));
+        assert!(sdl.contains("\n  fill_level_2: Float\n"));
+        assert!(sdl.contains("\n  _3dModel: String\n"));
+        assert!(sdl.contains("\n  serial_number: String\n"));
+    }
+}
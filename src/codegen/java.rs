@@ -0,0 +1,277 @@
+use std::fmt::Write;
+
+use crate::{
+    codegen::classes::{Class, Enum, TypeRef},
+    smartdata::models::ParsedModel,
+};
+
+const KEYWORDS: [&str; 54] = [
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "try",
+    "void",
+    "volatile",
+    "while",
+    "_",
+    "true",
+    "false",
+    "null",
+];
+
+/// Generates a Java record with Jackson annotations, nested types are declared in its body.
+pub fn generate(model: &ParsedModel) -> String {
+    let class = Class::new(model);
+
+    let mut imports = vec![
+        "com.fasterxml.jackson.annotation.JsonInclude",
+        "com.fasterxml.jackson.annotation.JsonProperty",
+    ];
+    if class.uses(|typ| *typ == TypeRef::Json) {
+        imports.push("com.fasterxml.jackson.databind.JsonNode");
+    }
+    let time_imports = [
+        (TypeRef::Date, "java.time.LocalDate"),
+        (TypeRef::Time, "java.time.LocalTime"),
+        (TypeRef::DateTime, "java.time.OffsetDateTime"),
+    ];
+    for (time, import) in time_imports {
+        if class.uses(|typ| *typ == time) {
+            imports.push(import);
+        }
+    }
+    if class.uses(|typ| matches!(typ, TypeRef::List(_))) {
+        imports.push("java.util.List");
+    }
+
+    let mut java = String::new();
+    writeln!(
+        java,
+        "// Generated from the smart data model {}.",
+        model.name
+    )
+    .unwrap();
+    writeln!(java).unwrap();
+    for import in imports {
+        writeln!(java, "import {import};").unwrap();
+    }
+    writeln!(java).unwrap();
+    write_record(&mut java, &class, "", true);
+    java
+}
+
+fn write_record(java: &mut String, class: &Class, indent: &str, is_top_level: bool) {
+    let inner = format!("{indent}    ");
+
+    let documented: Vec<_> = class
+        .fields
+        .iter()
+        .filter(|f| !f.description.is_empty())
+        .collect();
+    if !class.description.is_empty() || !documented.is_empty() {
+        writeln!(java, "{indent}/**").unwrap();
+        if !class.description.is_empty() {
+            writeln!(java, "{indent} * {}", class.description).unwrap();
+        }
+        if !class.description.is_empty() && !documented.is_empty() {
+            writeln!(java, "{indent} *").unwrap();
+        }
+        for field in documented {
+            writeln!(
+                java,
+                "{indent} * @param {} {}",
+                ident(&field.ident),
+                field.description
+            )
+            .unwrap();
+        }
+        writeln!(java, "{indent} */").unwrap();
+    }
+    writeln!(java, "{indent}@JsonInclude(JsonInclude.Include.NON_NULL)").unwrap();
+    writeln!(java, "{indent}public record {}(", class.name).unwrap();
+
+    let last = class.fields.len().saturating_sub(1);
+    for (i, field) in class.fields.iter().enumerate() {
+        let annotation = if field.required {
+            format!("@JsonProperty(value = \"{}\", required = true)", field.name)
+        } else {
+            format!("@JsonProperty(\"{}\")", field.name)
+        };
+        let separator = if i == last { "" } else { "," };
+        writeln!(
+            java,
+            "{inner}{annotation} {} {}{separator}",
+            java_type(&field.typ, field.required),
+            ident(&field.ident)
+        )
+        .unwrap();
+    }
+
+    let has_body = is_top_level && !(class.enums.is_empty() && class.classes.is_empty());
+    if !has_body {
+        writeln!(java, "{indent}) {{}}").unwrap();
+        return;
+    }
+
+    writeln!(java, "{indent}) {{").unwrap();
+    for e in class.enums.iter() {
+        writeln!(java).unwrap();
+        write_enum(java, e, &inner);
+    }
+    for nested in class.classes.iter() {
+        writeln!(java).unwrap();
+        write_record(java, nested, &inner, false);
+    }
+    writeln!(java, "{indent}}}").unwrap();
+}
+
+fn write_enum(java: &mut String, e: &Enum, indent: &str) {
+    writeln!(java, "{indent}public enum {} {{", e.name).unwrap();
+    let last = e.constants.len().saturating_sub(1);
+    for (i, constant) in e.constants.iter().enumerate() {
+        let separator = if i == last { "" } else { "," };
+        writeln!(
+            java,
+            "{indent}    @JsonProperty(\"{}\") {}{separator}",
+            constant.value, constant.screaming
+        )
+        .unwrap();
+    }
+    writeln!(java, "{indent}}}").unwrap();
+}
+
+/// Required numbers and booleans are primitives, everything else may be null.
+fn java_type(typ: &TypeRef, required: bool) -> String {
+    match typ {
+        TypeRef::String => String::from("String"),
+        TypeRef::Integer if required => String::from("long"),
+        TypeRef::Integer => String::from("Long"),
+        TypeRef::Number if required => String::from("double"),
+        TypeRef::Number => String::from("Double"),
+        TypeRef::Boolean if required => String::from("boolean"),
+        TypeRef::Boolean => String::from("Boolean"),
+        TypeRef::DateTime => String::from("OffsetDateTime"),
+        TypeRef::Date => String::from("LocalDate"),
+        TypeRef::Time => String::from("LocalTime"),
+        TypeRef::Json => String::from("JsonNode"),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::List(item) => format!("List<{}>", java_type(item, false)),
+    }
+}
+
+fn ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::generate;
+
+    #[test]
+    fn create_record() {
+        let java = "// Generated from the smart data model Device.\n\n\
+            import com.fasterxml.jackson.annotation.JsonInclude;\n\
+            import com.fasterxml.jackson.annotation.JsonProperty;\n\
+            import java.util.List;\n\n\
+            /**\n \
+            * @param batteryLevel Battery level. Units: percent\n \
+            */\n\
+            @JsonInclude(JsonInclude.Include.NON_NULL)\n\
+            public record Device(\n    \
+            @JsonProperty(value = \"id\", required = true) String id,\n    \
+            @JsonProperty(\"batteryLevel\") Double batteryLevel,\n    \
+            @JsonProperty(\"category\") List<DeviceCategory> category\n\
+            ) {\n\n    \
+            public enum DeviceCategory {\n        \
+            @JsonProperty(\"sensor\") SENSOR,\n        \
+            @JsonProperty(\"smartMeter\") SMART_METER\n    \
+            }\n\
+            }\n";
+
+        let model = ParsedModel {
+            name: "Device".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml(
+                    "batteryLevel",
+                    "{type: number, description: Battery level., x-ngsi: {units: percent}}",
+                ),
+                Property::from_yaml(
+                    "category",
+                    "{type: array, items: {type: string, enum: [sensor, smartMeter]}}",
+                ),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(java, generate(&model));
+    }
+
+    #[test]
+    fn escape_keywords() {
+        let model = ParsedModel {
+            name: "Device".to_string(),
+            properties: vec![
+                Property::from_yaml("native", "{type: boolean}"),
+                Property::from_yaml("volatile", "{type: number}"),
+            ],
+            ..Default::default()
+        };
+
+        let java = generate(&model);
+        assert!(java.contains("@JsonProperty(\"native\") Boolean native_,"));
+        assert!(java.contains("@JsonProperty(\"volatile\") Double volatile_\n"));
+    }
+}
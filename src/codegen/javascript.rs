@@ -1,6 +1,196 @@
-use std::fmt::Display;
+use std::fmt::{Display, Write};
+
+use crate::{
+    codegen::{one_line, types::FieldType},
+    smartdata::models::{ParsedModel, Property},
+};
+
+/// Formats `validate()` can check, with the expression that tests `value`.
+const FORMAT_CHECKS: [(&str, &str); 5] = [
+    ("date-time", "!Number.isNaN(Date.parse(value))"),
+    ("date", r"/^\d{4}-\d{2}-\d{2}$/.test(value)"),
+    (
+        "time",
+        r"/^\d{2}:\d{2}(:\d{2}(\.\d+)?)?(Z|[+-]\d{2}:\d{2})?$/.test(value)",
+    ),
+    // `URL.canParse` is missing in the Node versions Node-RED still supports
+    (
+        "uri",
+        "{ try { new URL(value); return true; } catch { return false; } }",
+    ),
+    ("email", r"/^[^@\s]+@[^@\s]+$/.test(value)"),
+];
+
+const FUNCTIONS: &str = r#"/**
+ * Checks required fields, enum members and formats of an entity in key-values form.
+ * @param {Object} entity
+ * @returns {string[]} The errors, empty if the entity is valid.
+ */
+export function validate(entity) {
+  const errors = [];
+  for (const name of REQUIRED) {
+    if (entity[name] === undefined || entity[name] === null) {
+      errors.push(`${name} is required`);
+    }
+  }
+  for (const [name, values] of Object.entries(ENUMS)) {
+    if (entity[name] !== undefined && !values.includes(entity[name])) {
+      errors.push(`${name} must be one of ${values.join(", ")}`);
+    }
+  }
+  for (const [name, format] of Object.entries(FORMATS)) {
+    if (entity[name] !== undefined && !FORMAT_CHECKS[format](entity[name])) {
+      errors.push(`${name} must be a valid ${format}`);
+    }
+  }
+  return errors;
+}
+
+/**
+ * Converts an entity in key-values form to NGSI-LD normalized form.
+ * @param {Object} entity
+ * @returns {Object}
+ */
+export function toNormalized(entity) {
+  const normalized = { id: entity.id, type: entity.type ?? TYPE };
+  for (const [name, value] of Object.entries(entity)) {
+    if (name === "id" || name === "type" || name === "@context" || value === undefined) {
+      continue;
+    }
+    const kind = KINDS[name] ?? "Property";
+    normalized[name] = kind === "Relationship" ? { type: kind, object: value } : { type: kind, value };
+  }
+  if (entity["@context"] !== undefined) {
+    normalized["@context"] = entity["@context"];
+  }
+  return normalized;
+}
+
+/**
+ * Converts an entity in NGSI-LD normalized form to key-values form.
+ * @param {Object} normalized
+ * @returns {Object}
+ */
+export function fromNormalized(normalized) {
+  const entity = {};
+  for (const [name, attribute] of Object.entries(normalized)) {
+    if (name === "id" || name === "type" || name === "@context" || attribute === null || typeof attribute !== "object" || Array.isArray(attribute)) {
+      entity[name] = attribute;
+    } else if (attribute.type === "Relationship") {
+      entity[name] = attribute.object;
+    } else {
+      entity[name] = attribute.value;
+    }
+  }
+  return entity;
+}
+"#;
+
+/// Generates an ES module with a JSDoc typed factory of the entity, a validator and
+/// conversions from and to NGSI-LD normalized form.
+pub fn generate(model: &ParsedModel) -> String {
+    let checked: Vec<_> = model.properties.iter().filter(|p| p.checked).collect();
+
+    let required: Vec<_> = checked
+        .iter()
+        .filter(|p| p.required)
+        .map(|p| p.name.as_str())
+        .collect();
+    let mut enums = serde_json::Map::new();
+    let mut formats = serde_json::Map::new();
+    let mut kinds = serde_json::Map::new();
+    for property in checked.iter() {
+        match FieldType::from_property(property) {
+            FieldType::Enum(values) => {
+                enums.insert(property.name.clone(), serde_json::json!(values));
+            }
+            FieldType::Geo(_) => {
+                kinds.insert(property.name.clone(), "GeoProperty".into());
+            }
+            FieldType::Relationship(_) => {
+                kinds.insert(property.name.clone(), "Relationship".into());
+            }
+            _ => {}
+        }
+        let format = property.format.as_deref().unwrap_or_default();
+        if FORMAT_CHECKS.iter().any(|(known, _)| *known == format) {
+            formats.insert(property.name.clone(), format.into());
+        }
+    }
+    let format_checks: Vec<_> = FORMAT_CHECKS
+        .iter()
+        .filter(|(format, _)| formats.values().any(|f| f == format))
+        .map(|(format, check)| format!("  \"{format}\": (value) => {check},"))
+        .collect();
+
+    let constants = [
+        ("TYPE", Type::String, serde_json::json!(model.name)),
+        ("REQUIRED", Type::Array, serde_json::json!(required)),
+        ("ENUMS", Type::Object, serde_json::Value::Object(enums)),
+        ("FORMATS", Type::Object, serde_json::Value::Object(formats)),
+        ("KINDS", Type::Object, serde_json::Value::Object(kinds)),
+    ];
+
+    let mut js = String::new();
+    writeln!(js, "// Generated from the smart data model {}.", model.name).unwrap();
+    writeln!(js).unwrap();
+    for (name, typ, value) in constants {
+        let variable = Variable {
+            declaration: Declaration::Const,
+            typ,
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        writeln!(js, "{}", variable.with_jsdoc()).unwrap();
+        writeln!(js).unwrap();
+    }
+    writeln!(js, "{}", Type::Object.to_jsdoc()).unwrap();
+    writeln!(js, "const FORMAT_CHECKS = {{").unwrap();
+    for check in format_checks {
+        writeln!(js, "{check}").unwrap();
+    }
+    writeln!(js, "}};").unwrap();
+    writeln!(js).unwrap();
+
+    js.push_str(&typedef(model));
+    writeln!(js).unwrap();
+    writeln!(js, "/**").unwrap();
+    writeln!(js, " * Creates a {} in key-values form.", model.name).unwrap();
+    writeln!(js, " * @param {{Object}} [data]").unwrap();
+    writeln!(js, " * @returns {{{}}}", model.name).unwrap();
+    writeln!(js, " */").unwrap();
+    writeln!(js, "export function create{}(data = {{}}) {{", model.name).unwrap();
+    writeln!(js, "  return {{ ...data, type: TYPE }};").unwrap();
+    writeln!(js, "}}").unwrap();
+    writeln!(js).unwrap();
+    js.push_str(FUNCTIONS);
+    js
+}
+
+/// Generates a JSDoc typedef of the entity with all checked properties.
+fn typedef(model: &ParsedModel) -> String {
+    let mut js = String::new();
+    writeln!(js, "/**").unwrap();
+    writeln!(js, " * {}", one_line(&model.description)).unwrap();
+    writeln!(js, " * @typedef {{Object}} {}", model.name).unwrap();
+    for property in model.properties.iter().filter(|p| p.checked) {
+        let name = if property.required {
+            property.name.clone()
+        } else {
+            format!("[{}]", property.name)
+        };
+        let description = one_line(property.description.as_deref().unwrap_or_default());
+        writeln!(
+            js,
+            " * @property {{{}}} {name} - {description}",
+            Type::from_property(property)
+        )
+        .unwrap();
+    }
+    writeln!(js, " */").unwrap();
+    js
+}
 
-#[allow(dead_code)]
 pub struct Variable {
     declaration: Declaration,
     typ: Type,
@@ -8,7 +198,6 @@ pub struct Variable {
     value: String,
 }
 
-#[allow(dead_code)]
 impl Variable {
     fn with_jsdoc(&self) -> String {
         format!(
@@ -37,9 +226,21 @@ pub enum Type {
     Null,
     Symbol,
     Object,
+    Array,
 }
 
 impl Type {
+    pub fn from_property(property: &Property) -> Self {
+        match property.json_types().as_slice() {
+            ["string"] => Type::String,
+            ["number"] | ["integer"] => Type::Number,
+            ["boolean"] => Type::Boolean,
+            ["array"] => Type::Array,
+            ["null"] => Type::Null,
+            _ => Type::Object,
+        }
+    }
+
     pub fn to_jsdoc(&self) -> String {
         format!("/** @type {{{self}}} */")
     }
@@ -56,6 +257,7 @@ impl Display for Type {
             Type::Null => write!(f, "null"),
             Type::Symbol => write!(f, "symbol"),
             Type::Object => write!(f, "object"),
+            Type::Array => write!(f, "Array"),
         }
     }
 }
@@ -79,9 +281,12 @@ impl Display for Declaration {
 
 #[cfg(test)]
 mod tests {
-    use crate::codegen::javascript::{Declaration, Type};
+    use crate::{
+        codegen::javascript::{Declaration, Type},
+        smartdata::models::{ParsedModel, Property},
+    };
 
-    use super::Variable;
+    use super::{generate, typedef, Variable};
 
     #[test]
     fn create_var() {
@@ -96,4 +301,57 @@ mod tests {
 
         assert_eq!(javascript, var.with_jsdoc());
     }
+
+    #[test]
+    fn create_typedef() {
+        let javascript = "/**\n * A parking spot.\n * @typedef {Object} ParkingSpot\n \
+            * @property {string} id - Unique identifier.\n \
+            * @property {number} [width] - Width in meters.\n */\n";
+
+        let property = |name: &str, typ: &str, description: &str, required: bool| Property {
+            name: name.to_string(),
+            typ: Some(typ.to_string()),
+            description: Some(description.to_string()),
+            required,
+            checked: true,
+            ..Default::default()
+        };
+        let model = ParsedModel {
+            name: "ParkingSpot".to_string(),
+            description: "A parking spot.".to_string(),
+            properties: vec![
+                property("id", "string", "Unique identifier.", true),
+                property("width", "number", "Width in\n meters.", false),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(javascript, typedef(&model));
+    }
+
+    #[test]
+    fn create_module() {
+        let model = ParsedModel {
+            name: "ParkingSpot".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml("status", "{type: string, enum: [free, occupied]}"),
+                Property::from_yaml("dateObserved", "{type: string, format: date-time}"),
+                Property::from_yaml("refParkingSite", "{type: string, format: uri}"),
+            ],
+            ..Default::default()
+        };
+
+        let javascript = generate(&model);
+
+        assert!(javascript.contains("/** @type {Array} */\nconst REQUIRED = [\"id\"];"));
+        assert!(javascript.contains("const ENUMS = {\"status\":[\"free\",\"occupied\"]};"));
+        assert!(javascript.contains("const KINDS = {\"refParkingSite\":\"Relationship\"};"));
+        assert!(javascript.contains("  \"date-time\": (value) =>"));
+        assert!(javascript.contains("export function createParkingSpot(data = {}) {"));
+        assert!(javascript.contains("export function fromNormalized(normalized) {"));
+    }
 }
@@ -0,0 +1,222 @@
+use serde_json::{json, Map, Value};
+
+use crate::{
+    codegen::{case::to_sentence_case, one_line, schema::number, types::FieldType},
+    smartdata::models::{GeoProperty, ParsedModel, Property},
+};
+
+/// Generates the data schema and the UI schema of a JSON Forms form, as
+/// `{"schema": ..., "uischema": ...}`.
+pub fn generate(model: &ParsedModel) -> String {
+    let properties: Vec<_> = model
+        .properties
+        .iter()
+        .filter(|p| p.checked)
+        .cloned()
+        .collect();
+
+    let mut schema = object_schema(&properties);
+    schema.insert("title".to_string(), json!(model.name));
+    if !model.description.is_empty() {
+        schema.insert(
+            "description".to_string(),
+            json!(one_line(&model.description)),
+        );
+    }
+    let uischema = json!({
+        "type": "VerticalLayout",
+        "elements": controls(&properties, "#"),
+    });
+
+    let mut json = serde_json::to_string_pretty(&json!({
+        "schema": schema,
+        "uischema": uischema,
+    }))
+    .unwrap();
+    json.push('\n');
+    json
+}
+
+fn object_schema(properties: &[Property]) -> Map<String, Value> {
+    let mut members = Map::new();
+    for property in properties {
+        members.insert(property.name.clone(), property_schema(property));
+    }
+    let required: Vec<_> = properties
+        .iter()
+        .filter(|p| p.required)
+        .map(|p| p.name.as_str())
+        .collect();
+
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!("object"));
+    schema.insert("properties".to_string(), Value::Object(members));
+    if !required.is_empty() {
+        schema.insert("required".to_string(), json!(required));
+    }
+    schema
+}
+
+/// The schema of a property with the shapes the JSON Forms renderers know, like date pickers
+/// for formats and nested controls for objects.
+fn property_schema(property: &Property) -> Value {
+    let mut schema = value_schema(property, &FieldType::from_property(property));
+    if let Value::Object(schema) = &mut schema {
+        schema.insert("title".to_string(), json!(to_sentence_case(&property.name)));
+        if let Some(description) = &property.description {
+            schema.insert("description".to_string(), json!(one_line(description)));
+        }
+    }
+    schema
+}
+
+fn value_schema(property: &Property, field: &FieldType) -> Value {
+    let mut schema = match field {
+        FieldType::String | FieldType::Enum(_) => json!({"type": "string"}),
+        FieldType::Integer => json!({"type": "integer"}),
+        FieldType::Number => json!({"type": "number"}),
+        FieldType::Boolean => json!({"type": "boolean"}),
+        FieldType::DateTime => json!({"type": "string", "format": "date-time"}),
+        FieldType::Date => json!({"type": "string", "format": "date"}),
+        FieldType::Time => json!({"type": "string", "format": "time"}),
+        FieldType::Uri | FieldType::Relationship(_) => json!({"type": "string", "format": "uri"}),
+        FieldType::Geo(variant) => geometry_schema(variant.as_deref()),
+        FieldType::Object => Value::Object(object_schema(&property.members())),
+        FieldType::Array(item) => {
+            let item_property = property.item().unwrap_or_default();
+            json!({
+                "type": "array",
+                "items": value_schema(&item_property, item),
+            })
+        }
+    };
+    let Value::Object(object) = &mut schema else {
+        return schema;
+    };
+
+    if let FieldType::Enum(values) = field {
+        object.insert("enum".to_string(), json!(values));
+    }
+    let constraints = [
+        ("minimum", property.minimum.map(number)),
+        ("maximum", property.maximum.map(number)),
+        ("minLength", property.min_length.map(|v| json!(v))),
+        ("maxLength", property.max_length.map(|v| json!(v))),
+        ("pattern", property.pattern.as_ref().map(|v| json!(v))),
+    ];
+    for (keyword, value) in constraints {
+        if let Some(value) = value {
+            object.insert(keyword.to_string(), value);
+        }
+    }
+    schema
+}
+
+/// A GeoJSON geometry, the coordinates are nested as deep as the geometry type needs.
+fn geometry_schema(variant: Option<&str>) -> Value {
+    let variant = variant.unwrap_or(GeoProperty::Point.str());
+    let depth = match variant {
+        "Point" => 1,
+        "LineString" | "MultiPoint" => 2,
+        "Polygon" | "MultiLineString" => 3,
+        _ => 4,
+    };
+    let mut coordinates = json!({"type": "number"});
+    for _ in 0..depth {
+        coordinates = json!({"type": "array", "items": coordinates});
+    }
+
+    json!({
+        "type": "object",
+        "properties": {
+            "type": {"type": "string", "enum": [variant]},
+            "coordinates": coordinates,
+        },
+        "required": ["type", "coordinates"],
+    })
+}
+
+/// A control for each property, members of objects are grouped, and labels carry the units.
+fn controls(properties: &[Property], scope: &str) -> Vec<Value> {
+    properties
+        .iter()
+        .map(|property| {
+            let scope = format!("{scope}/properties/{}", property.name);
+            let mut label = to_sentence_case(&property.name);
+            if let Some(units) = property.units() {
+                label = format!("{label} ({units})");
+            }
+
+            let members = property.members();
+            if FieldType::from_property(property) == FieldType::Object && !members.is_empty() {
+                json!({
+                    "type": "Group",
+                    "label": label,
+                    "elements": controls(&members, &scope),
+                })
+            } else {
+                json!({
+                    "type": "Control",
+                    "scope": scope,
+                    "label": label,
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::generate;
+
+    #[test]
+    fn create_form() {
+        let model = ParsedModel {
+            name: "WeatherObserved".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml(
+                    "temperature",
+                    "{type: number, description: Air temperature., x-ngsi: {units: celsius}}",
+                ),
+                Property::from_yaml("location", "{}"),
+                Property::from_yaml(
+                    "address",
+                    "{type: object, properties: {streetAddress: {type: string}}}",
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let form: Value = serde_json::from_str(&generate(&model)).unwrap();
+
+        assert_eq!(
+            form["schema"]["properties"]["temperature"],
+            json!({"type": "number", "title": "Temperature", "description": "Air temperature."})
+        );
+        assert_eq!(form["schema"]["required"], json!(["id"]));
+        assert_eq!(
+            form["schema"]["properties"]["location"]["properties"]["coordinates"],
+            json!({"type": "array", "items": {"type": "number"}})
+        );
+        assert_eq!(
+            form["uischema"]["elements"][1],
+            json!({
+                "type": "Control",
+                "scope": "#/properties/temperature",
+                "label": "Temperature (celsius)",
+            })
+        );
+        assert_eq!(
+            form["uischema"]["elements"][3]["elements"][0]["scope"],
+            json!("#/properties/address/properties/streetAddress")
+        );
+    }
+}
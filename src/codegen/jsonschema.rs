@@ -0,0 +1,297 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use reqwest::Url;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    codegen::schema::property_schema,
+    smartdata::models::{fetch_text, ParsedModel},
+};
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Nesting depth after which `$ref`s are no longer followed, so cycles end.
+const MAX_REF_DEPTH: usize = 16;
+
+/// Remote documents which `$ref`s point to, by their url.
+pub type Documents = BTreeMap<String, Value>;
+
+/// Generates a standalone JSON Schema which only allows the checked properties.
+/// `$ref`s are inlined from the model and `documents`, see `fetch_documents`.
+/// Fails if a `$ref` can not be resolved, instead of loosening the schema.
+pub fn generate(model: &ParsedModel, documents: &Documents) -> Result<String, anyhow::Error> {
+    let model_document = ModelDocument::new(model);
+
+    let checked: Vec<_> = model.properties.iter().filter(|p| p.checked).collect();
+    let mut properties = Map::new();
+    let mut unresolved = vec![];
+    for property in checked.iter() {
+        let mut schema = Value::Object(property_schema(property));
+        let mut inliner = Inliner {
+            model: &model_document,
+            documents,
+            unresolved: &mut unresolved,
+        };
+        inliner.inline(&mut schema, &model_document.url, 0);
+        properties.insert(property.name.clone(), schema);
+    }
+    if !unresolved.is_empty() {
+        anyhow::bail!("the $refs {} can not be resolved", unresolved.join(", "));
+    }
+    let required: Vec<_> = checked
+        .iter()
+        .filter(|p| p.required || model.required.contains(&p.name))
+        .map(|p| p.name.as_str())
+        .collect();
+
+    let mut schema = json!({
+        "$schema": DRAFT,
+        "title": model.name,
+    });
+    if !model.description.is_empty() {
+        schema["description"] = json!(model.description);
+    }
+    schema["type"] = json!("object");
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    schema["properties"] = Value::Object(properties);
+    schema["additionalProperties"] = json!(false);
+
+    let mut json = serde_json::to_string_pretty(&schema).unwrap();
+    json.push('\n');
+    Ok(json)
+}
+
+/// Fetches the remote documents the `$ref`s of the model point to, and those the definitions in
+/// them point to in turn. Documents which fail to load are left out, `generate` names the refs
+/// into them.
+pub async fn fetch_documents(model: &ParsedModel) -> Documents {
+    let model_document = ModelDocument::new(model);
+    let mut pending: Vec<_> = refs(&model_document.root, &model_document.url)
+        .into_iter()
+        .map(|target| (target, 0))
+        .collect();
+    let mut documents = Documents::new();
+    let mut visited = BTreeSet::new();
+    let mut failed = BTreeSet::new();
+
+    while let Some(((url, pointer), depth)) = pending.pop() {
+        if url == model_document.url
+            || depth >= MAX_REF_DEPTH
+            || failed.contains(&url)
+            || !visited.insert((url.clone(), pointer.clone()))
+        {
+            continue;
+        }
+        if !documents.contains_key(&url) {
+            let document = fetch_text(&url)
+                .await
+                .ok()
+                .and_then(|text| serde_yaml::from_str::<Value>(&text).ok());
+            match document {
+                Some(document) => documents.insert(url.clone(), document),
+                None => {
+                    failed.insert(url);
+                    continue;
+                }
+            };
+        }
+        if let Some(definition) = documents[&url].pointer(&pointer) {
+            pending.extend(
+                refs(definition, &url)
+                    .into_iter()
+                    .map(|target| (target, depth + 1)),
+            );
+        }
+    }
+    documents
+}
+
+/// The upstream `model.yaml`, which local `$ref`s point into.
+struct ModelDocument {
+    url: String,
+    root: Value,
+}
+
+impl ModelDocument {
+    fn new(model: &ParsedModel) -> Self {
+        let root = serde_yaml::from_str::<Value>(&model.raw)
+            .ok()
+            .and_then(|raw| raw.as_object()?.values().next().cloned())
+            .unwrap_or_default();
+        ModelDocument {
+            url: model.yaml_link(),
+            root,
+        }
+    }
+}
+
+struct Inliner<'a> {
+    model: &'a ModelDocument,
+    documents: &'a Documents,
+    unresolved: &'a mut Vec<String>,
+}
+
+impl Inliner<'_> {
+    /// Replaces `$ref`s with the definitions they point to, so validators need no network access.
+    /// `base` is the url of the document `value` comes from. Refs which point nowhere and cycles
+    /// are collected into `unresolved`.
+    fn inline(&mut self, value: &mut Value, base: &str, depth: usize) {
+        match value {
+            Value::Object(object) => {
+                if let Some(reference) = object.remove("$ref") {
+                    let reference = reference.as_str().unwrap_or_default().to_string();
+                    let target = resolve(base, &reference).filter(|_| depth < MAX_REF_DEPTH);
+                    let definition = target.as_ref().and_then(|(url, pointer)| {
+                        let document = if *url == self.model.url {
+                            Some(&self.model.root)
+                        } else {
+                            self.documents.get(url)
+                        };
+                        document?.pointer(pointer)?.as_object()
+                    });
+                    match (target.as_ref(), definition) {
+                        (Some((url, _)), Some(definition)) => {
+                            for (key, definition) in definition.iter() {
+                                object
+                                    .entry(key.clone())
+                                    .or_insert_with(|| definition.clone());
+                            }
+                            // Refs of the definition are relative to its own document
+                            self.inline(value, url, depth + 1);
+                            return;
+                        }
+                        _ if !self.unresolved.contains(&reference) => {
+                            self.unresolved.push(reference)
+                        }
+                        _ => {}
+                    }
+                }
+                for nested in object.values_mut() {
+                    self.inline(nested, base, depth);
+                }
+            }
+            Value::Array(array) => {
+                for nested in array.iter_mut() {
+                    self.inline(nested, base, depth);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The url of the document and the json pointer into it, which `reference` found in the
+/// document at `base` points to.
+fn resolve(base: &str, reference: &str) -> Option<(String, String)> {
+    let mut url = Url::parse(base).ok()?.join(reference).ok()?;
+    let pointer = url.fragment().unwrap_or_default().to_string();
+    url.set_fragment(None);
+    Some((url.to_string(), pointer))
+}
+
+/// All `$ref`s in `value`, resolved against `base`.
+fn refs(value: &Value, base: &str) -> Vec<(String, String)> {
+    match value {
+        Value::Object(object) => object
+            .iter()
+            .flat_map(|(key, nested)| match (key.as_str(), nested) {
+                ("$ref", Value::String(reference)) => {
+                    resolve(base, reference).into_iter().collect()
+                }
+                _ => refs(nested, base),
+            })
+            .collect(),
+        Value::Array(array) => array.iter().flat_map(|nested| refs(nested, base)).collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::{generate, Documents};
+
+    #[test]
+    fn trimmed_schema() {
+        let raw = "\
+Building:
+  definitions:
+    Floor: {type: integer, minimum: 0}
+  properties:
+    floors: {type: array, items: {$ref: '#/definitions/Floor'}}
+";
+        let model = ParsedModel {
+            name: "Building".to_string(),
+            raw: raw.to_string(),
+            required: vec!["id".to_string(), "type".to_string()],
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property {
+                    required: true,
+                    ..Property::from_yaml("type", "{type: string, enum: [Building]}")
+                },
+                Property {
+                    checked: false,
+                    ..Property::from_yaml("name", "{type: string}")
+                },
+                Property::from_yaml(
+                    "floors",
+                    "{type: array, items: {$ref: '#/definitions/Floor'}}",
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let schema: Value =
+            serde_json::from_str(&generate(&model, &Documents::new()).unwrap()).unwrap();
+
+        assert_eq!(
+            schema["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        assert_eq!(schema["required"], json!(["id", "type"]));
+        assert!(schema["properties"].get("name").is_none());
+        assert_eq!(schema["properties"]["floors"]["items"]["minimum"], 0);
+        assert_eq!(schema["additionalProperties"], false);
+
+        let mut remote = model.clone();
+        remote.properties.push(Property::from_yaml(
+            "addresses",
+            "{type: array, items: {$ref: 'https://smart-data-models.github.io/data-models/common-schema.json#/definitions/Address'}}",
+        ));
+        assert!(generate(&remote, &Documents::new())
+            .unwrap_err()
+            .to_string()
+            .contains("common-schema.json#/definitions/Address"));
+
+        // Refs in a remote document are relative to it
+        let documents = Documents::from([
+            (
+                "https://smart-data-models.github.io/data-models/common-schema.json".to_string(),
+                json!({"definitions": {
+                    "Address": {"type": "object", "properties": {
+                        "postalCode": {"$ref": "#/definitions/PostalCode"},
+                        "country": {"$ref": "countries.json#/definitions/Country"},
+                    }},
+                    "PostalCode": {"type": "string", "maxLength": 10},
+                }}),
+            ),
+            (
+                "https://smart-data-models.github.io/data-models/countries.json".to_string(),
+                json!({"definitions": {"Country": {"type": "string", "minLength": 2}}}),
+            ),
+        ]);
+        let schema: Value = serde_json::from_str(&generate(&remote, &documents).unwrap()).unwrap();
+        let address = &schema["properties"]["addresses"]["items"];
+        assert_eq!(address["properties"]["postalCode"]["maxLength"], 10);
+        assert_eq!(address["properties"]["country"]["minLength"], 2);
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt::Write;
+
+use crate::{
+    codegen::classes::{Class, Enum, TypeRef},
+    smartdata::models::ParsedModel,
+};
+
+const KEYWORDS: [&str; 14] = [
+    "as",
+    "class",
+    "fun",
+    "in",
+    "interface",
+    "is",
+    "object",
+    "package",
+    "return",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+];
+
+/// Generates a Kotlin data class for kotlinx.serialization, nested types are declared in its body.
+pub fn generate(model: &ParsedModel) -> String {
+    let class = Class::new(model);
+
+    let mut imports = vec![
+        "kotlinx.serialization.SerialName",
+        "kotlinx.serialization.Serializable",
+    ];
+    let time_imports = [
+        (TypeRef::DateTime, "kotlinx.datetime.Instant"),
+        (TypeRef::Date, "kotlinx.datetime.LocalDate"),
+        (TypeRef::Time, "kotlinx.datetime.LocalTime"),
+    ];
+    for (time, import) in time_imports {
+        if class.uses(|typ| *typ == time) {
+            imports.push(import);
+        }
+    }
+    if class.uses(|typ| *typ == TypeRef::Json) {
+        imports.push("kotlinx.serialization.json.JsonElement");
+    }
+    imports.sort();
+
+    let mut kotlin = String::new();
+    writeln!(
+        kotlin,
+        "// Generated from the smart data model {}.",
+        model.name
+    )
+    .unwrap();
+    writeln!(kotlin).unwrap();
+    for import in imports {
+        writeln!(kotlin, "import {import}").unwrap();
+    }
+    writeln!(kotlin).unwrap();
+    write_class(&mut kotlin, &class, "", true);
+    kotlin
+}
+
+fn write_class(kotlin: &mut String, class: &Class, indent: &str, is_top_level: bool) {
+    let inner = format!("{indent}    ");
+
+    let documented: Vec<_> = class
+        .fields
+        .iter()
+        .filter(|f| !f.description.is_empty())
+        .collect();
+    if !class.description.is_empty() || !documented.is_empty() {
+        writeln!(kotlin, "{indent}/**").unwrap();
+        if !class.description.is_empty() {
+            writeln!(kotlin, "{indent} * {}", class.description).unwrap();
+        }
+        if !class.description.is_empty() && !documented.is_empty() {
+            writeln!(kotlin, "{indent} *").unwrap();
+        }
+        for field in documented {
+            writeln!(
+                kotlin,
+                "{indent} * @property {} {}",
+                field.ident, field.description
+            )
+            .unwrap();
+        }
+        writeln!(kotlin, "{indent} */").unwrap();
+    }
+    writeln!(kotlin, "{indent}@Serializable").unwrap();
+
+    // Data classes need at least one parameter
+    if class.fields.is_empty() {
+        write!(kotlin, "{indent}class {}", class.name).unwrap();
+    } else {
+        writeln!(kotlin, "{indent}data class {}(", class.name).unwrap();
+        for field in class.fields.iter() {
+            let typ = kotlin_type(&field.typ);
+            let typ = if field.required {
+                typ
+            } else {
+                format!("{typ}? = null")
+            };
+            writeln!(
+                kotlin,
+                "{inner}@SerialName(\"{}\") val {}: {typ},",
+                field.name,
+                ident(&field.ident)
+            )
+            .unwrap();
+        }
+        write!(kotlin, "{indent})").unwrap();
+    }
+
+    let has_body = is_top_level && !(class.enums.is_empty() && class.classes.is_empty());
+    if !has_body {
+        writeln!(kotlin).unwrap();
+        return;
+    }
+
+    writeln!(kotlin, " {{").unwrap();
+    for e in class.enums.iter() {
+        writeln!(kotlin).unwrap();
+        write_enum(kotlin, e, &inner);
+    }
+    for nested in class.classes.iter() {
+        writeln!(kotlin).unwrap();
+        write_class(kotlin, nested, &inner, false);
+    }
+    writeln!(kotlin, "{indent}}}").unwrap();
+}
+
+fn write_enum(kotlin: &mut String, e: &Enum, indent: &str) {
+    writeln!(kotlin, "{indent}@Serializable").unwrap();
+    writeln!(kotlin, "{indent}enum class {} {{", e.name).unwrap();
+    for constant in e.constants.iter() {
+        writeln!(
+            kotlin,
+            "{indent}    @SerialName(\"{}\") {},",
+            constant.value, constant.screaming
+        )
+        .unwrap();
+    }
+    writeln!(kotlin, "{indent}}}").unwrap();
+}
+
+fn kotlin_type(typ: &TypeRef) -> String {
+    match typ {
+        TypeRef::String => String::from("String"),
+        TypeRef::Integer => String::from("Long"),
+        TypeRef::Number => String::from("Double"),
+        TypeRef::Boolean => String::from("Boolean"),
+        TypeRef::DateTime => String::from("Instant"),
+        TypeRef::Date => String::from("LocalDate"),
+        TypeRef::Time => String::from("LocalTime"),
+        TypeRef::Json => String::from("JsonElement"),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::List(item) => format!("List<{}>", kotlin_type(item)),
+    }
+}
+
+fn ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("`{name}`")
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::generate;
+
+    #[test]
+    fn create_data_class() {
+        let kotlin = "// Generated from the smart data model Device.\n\n\
+            import kotlinx.datetime.Instant\n\
+            import kotlinx.serialization.SerialName\n\
+            import kotlinx.serialization.Serializable\n\n\
+            /**\n \
+            * A device.\n \
+            *\n \
+            * @property batteryLevel Battery level. Units: percent\n \
+            */\n\
+            @Serializable\n\
+            data class Device(\n    \
+            @SerialName(\"id\") val id: String,\n    \
+            @SerialName(\"batteryLevel\") val batteryLevel: Double? = null,\n    \
+            @SerialName(\"dateCreated\") val dateCreated: Instant? = null,\n    \
+            @SerialName(\"status\") val status: DeviceStatus? = null,\n\
+            ) {\n\n    \
+            @Serializable\n    \
+            enum class DeviceStatus {\n        \
+            @SerialName(\"ok\") OK,\n        \
+            @SerialName(\"outOfService\") OUT_OF_SERVICE,\n    \
+            }\n\
+            }\n";
+
+        let model = ParsedModel {
+            name: "Device".to_string(),
+            description: "A device.".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml(
+                    "batteryLevel",
+                    "{type: number, description: Battery level., x-ngsi: {units: percent}}",
+                ),
+                Property::from_yaml("dateCreated", "{type: string, format: date-time}"),
+                Property::from_yaml("status", "{type: string, enum: [ok, outOfService]}"),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(kotlin, generate(&model));
+    }
+}
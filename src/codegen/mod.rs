@@ -1 +1,168 @@
+pub mod avro;
+pub mod case;
+pub mod classes;
+pub mod csharp;
+pub mod go;
+pub mod graphql;
+pub mod java;
 pub mod javascript;
+pub mod jsonforms;
+pub mod jsonschema;
+pub mod kotlin;
+pub mod openapi;
+pub mod protobuf;
+pub mod schema;
+pub mod sql;
+pub mod templates;
+pub mod types;
+pub mod zod;
+
+use crate::smartdata::models::{ParsedModel, Property};
+
+use self::{jsonschema::Documents, sql::Dialect};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    JavaScript,
+    Sql(Dialect),
+    GraphQl,
+    Avro,
+    Protobuf,
+    OpenApi,
+    JsonSchema,
+    Go,
+    Java,
+    Kotlin,
+    CSharp,
+    Zod,
+    JsonForms,
+}
+
+impl Language {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Language::JavaScript => "JavaScript",
+            Language::Sql(Dialect::Postgres) => "SQL (PostgreSQL)",
+            Language::Sql(Dialect::Timescale) => "SQL (TimescaleDB)",
+            Language::Sql(Dialect::CrateDb) => "SQL (CrateDB / QuantumLeap)",
+            Language::GraphQl => "GraphQL",
+            Language::Avro => "Avro",
+            Language::Protobuf => "Protobuf",
+            Language::OpenApi => "OpenAPI components",
+            Language::JsonSchema => "JSON Schema",
+            Language::Go => "Go",
+            Language::Java => "Java",
+            Language::Kotlin => "Kotlin",
+            Language::CSharp => "C#",
+            Language::Zod => "Zod",
+            Language::JsonForms => "JSON Forms",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Language::JavaScript => "js",
+            Language::Sql(_) => "sql",
+            Language::GraphQl => "graphql",
+            Language::Avro => "avsc",
+            Language::Protobuf => "proto",
+            Language::OpenApi => "yaml",
+            Language::JsonSchema => "schema.json",
+            Language::Go => "go",
+            Language::Java => "java",
+            Language::Kotlin => "kt",
+            Language::CSharp => "cs",
+            Language::Zod => "ts",
+            Language::JsonForms => "jsonforms.json",
+        }
+    }
+
+    pub fn array() -> [Language; 15] {
+        [
+            Language::JavaScript,
+            Language::Sql(Dialect::Postgres),
+            Language::Sql(Dialect::Timescale),
+            Language::Sql(Dialect::CrateDb),
+            Language::GraphQl,
+            Language::Avro,
+            Language::Protobuf,
+            Language::OpenApi,
+            Language::JsonSchema,
+            Language::Go,
+            Language::Java,
+            Language::Kotlin,
+            Language::CSharp,
+            Language::Zod,
+            Language::JsonForms,
+        ]
+    }
+
+    pub fn from_name(language: &str) -> Option<Self> {
+        Language::array().into_iter().find(|l| l.str() == language)
+    }
+
+    /// Generates code for the checked properties of `model`. Schemas keep the field order and
+    /// numbers of `previous`, the earlier generated output, so consumers stay compatible.
+    /// JSON Schemas inline the `$ref`s into `documents`.
+    pub fn regenerate(
+        &self,
+        model: &ParsedModel,
+        previous: Option<&str>,
+        documents: &Documents,
+    ) -> Result<String, anyhow::Error> {
+        Ok(match self {
+            Language::JavaScript => javascript::generate(model),
+            Language::Sql(dialect) => sql::generate(model, *dialect),
+            Language::GraphQl => graphql::generate(model),
+            Language::Avro => avro::generate(model, previous),
+            Language::Protobuf => protobuf::generate(model, previous),
+            Language::OpenApi => openapi::generate(std::slice::from_ref(model)),
+            Language::JsonSchema => jsonschema::generate(model, documents)?,
+            Language::Go => go::generate(model),
+            Language::Java => java::generate(model),
+            Language::Kotlin => kotlin::generate(model),
+            Language::CSharp => csharp::generate(model),
+            Language::Zod => zod::generate(model),
+            Language::JsonForms => jsonforms::generate(model),
+        })
+    }
+}
+
+/// Joins the lines of a description, so it fits into a single comment line.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Makes identifiers unique by suffixing later duplicates with `_2`, `_3` and so on, e.g. when
+/// the enum values `on-street` and `onStreet` both become `ON_STREET`.
+fn unique_names(names: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut unique: Vec<String> = vec![];
+    for name in names {
+        let mut candidate = name.clone();
+        let mut suffix = 2;
+        while unique.contains(&candidate) {
+            candidate = format!("{name}_{suffix}");
+            suffix += 1;
+        }
+        unique.push(candidate);
+    }
+    unique
+}
+
+/// Orders `properties` like the names of `previous`, new properties follow in their own order.
+/// `name` gives the name a property has in the generated output.
+fn stable_order<'a>(
+    properties: &'a [Property],
+    previous: &[String],
+    name: fn(&str) -> String,
+) -> Vec<&'a Property> {
+    let mut ordered: Vec<_> = properties.iter().collect();
+    ordered.sort_by_key(|property| {
+        let name = name(&property.name);
+        previous
+            .iter()
+            .position(|previous| *previous == name)
+            .unwrap_or(usize::MAX)
+    });
+    ordered
+}
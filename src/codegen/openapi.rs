@@ -0,0 +1,116 @@
+use serde_json::{json, Map, Value};
+
+use crate::{
+    codegen::{schema::property_schema, types::FieldType},
+    smartdata::models::{ParsedModel, Property},
+};
+
+/// Generates an OpenAPI 3.1 document which only holds `components.schemas`, so it can be
+/// merged into existing specs. Relationships between the models become `$ref`s.
+pub fn generate(models: &[ParsedModel]) -> String {
+    let names: Vec<_> = models.iter().map(|model| model.name.as_str()).collect();
+
+    let mut schemas = Map::new();
+    for model in models {
+        schemas.insert(model.name.clone(), component(model, &names));
+    }
+
+    let document = json!({ "components": { "schemas": schemas } });
+    serde_yaml::to_string(&document).unwrap()
+}
+
+fn component(model: &ParsedModel, names: &[&str]) -> Value {
+    let checked: Vec<_> = model.properties.iter().filter(|p| p.checked).collect();
+
+    let mut properties = Map::new();
+    for property in checked.iter() {
+        properties.insert(property.name.clone(), property_component(property, names));
+    }
+    let required: Vec<_> = checked
+        .iter()
+        .filter(|p| p.required)
+        .map(|p| p.name.as_str())
+        .collect();
+
+    let mut component = json!({ "type": "object" });
+    if !model.description.is_empty() {
+        component["description"] = json!(model.description);
+    }
+    if !required.is_empty() {
+        component["required"] = json!(required);
+    }
+    component["properties"] = Value::Object(properties);
+    component
+}
+
+fn property_component(property: &Property, names: &[&str]) -> Value {
+    let FieldType::Relationship(target) = FieldType::from_property(property) else {
+        return Value::Object(property_schema(property));
+    };
+    // Targets which are not exported stay plain uris, a dangling `$ref` would break the spec
+    let Some(target) = names.iter().find(|name| {
+        name.eq_ignore_ascii_case(&target)
+            || target
+                .strip_suffix('s')
+                .is_some_and(|singular| name.eq_ignore_ascii_case(singular))
+    }) else {
+        return Value::Object(property_schema(property));
+    };
+
+    let reference = json!({ "$ref": format!("#/components/schemas/{target}") });
+    let mut component = match property.json_types().as_slice() {
+        ["array"] => json!({ "type": "array", "items": reference }),
+        _ => reference,
+    };
+    if let Some(description) = &property.description {
+        component["description"] = json!(description);
+    }
+    component
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::generate;
+
+    #[test]
+    fn components_with_refs() {
+        let device = ParsedModel {
+            name: "Device".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml("batteryLevel", "{type: number, minimum: 0, maximum: 1}"),
+                Property::from_yaml("refDeviceModel", "{type: string, format: uri}"),
+                Property::from_yaml("owner", "{type: array, items: {type: string}}"),
+            ],
+            ..Default::default()
+        };
+        let device_model = ParsedModel {
+            name: "DeviceModel".to_string(),
+            properties: vec![Property {
+                required: true,
+                ..Property::from_yaml("id", "{type: string}")
+            }],
+            ..Default::default()
+        };
+
+        let yaml = generate(&[device, device_model]);
+        let document: Value = serde_yaml::from_str(&yaml).unwrap();
+        let device = &document["components"]["schemas"]["Device"];
+
+        assert_eq!(device["required"][0], "id");
+        assert_eq!(device["properties"]["batteryLevel"]["maximum"], 1);
+        assert_eq!(
+            device["properties"]["refDeviceModel"]["$ref"],
+            "#/components/schemas/DeviceModel"
+        );
+        assert_eq!(device["properties"]["owner"]["type"], "array");
+        assert!(document["components"]["schemas"]["DeviceModel"].is_object());
+    }
+}
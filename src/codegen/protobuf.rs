@@ -0,0 +1,443 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+};
+
+use crate::{
+    codegen::{
+        case::{to_pascal_case, to_screaming_snake_case, to_snake_case},
+        one_line,
+        types::FieldType,
+        unique_names,
+    },
+    smartdata::models::{ParsedModel, Property},
+};
+
+/// Generates a proto3 message of the entity with all checked properties.
+/// Field and enum numbers of `previous`, an earlier generated file, are kept.
+pub fn generate(model: &ParsedModel, previous: Option<&str>) -> String {
+    let numbers = Numbers::parse(previous.unwrap_or_default());
+    let properties: Vec<_> = model
+        .properties
+        .iter()
+        .filter(|p| p.checked)
+        .cloned()
+        .collect();
+
+    let mut imports = BTreeSet::new();
+    let mut body = String::new();
+    write_message(
+        &mut body,
+        &Message {
+            name: &model.name,
+            description: &model.description,
+            properties: &properties,
+            scope: "",
+        },
+        &numbers,
+        &mut imports,
+        "",
+    );
+
+    let mut proto = String::new();
+    writeln!(proto, "syntax = \"proto3\";").unwrap();
+    writeln!(proto).unwrap();
+    let repo = model.repo.trim_start_matches("dataModel.");
+    if !repo.is_empty() {
+        writeln!(proto, "package smartdatamodels.{};", to_snake_case(repo)).unwrap();
+        writeln!(proto).unwrap();
+    }
+    for import in imports.iter() {
+        writeln!(proto, "import \"{import}\";").unwrap();
+    }
+    if !imports.is_empty() {
+        writeln!(proto).unwrap();
+    }
+    proto.push_str(&body);
+    proto
+}
+
+struct Message<'a> {
+    name: &'a str,
+    description: &'a str,
+    properties: &'a [Property],
+    /// Names of the enclosing messages, joined by dots.
+    scope: &'a str,
+}
+
+fn write_message(
+    proto: &mut String,
+    message: &Message,
+    numbers: &Numbers,
+    imports: &mut BTreeSet<&'static str>,
+    indent: &str,
+) {
+    let scope = join(message.scope, message.name);
+    let names: Vec<_> = message
+        .properties
+        .iter()
+        .map(|p| to_snake_case(&p.name))
+        .collect();
+    let (assigned, reserved) = numbers.assign(&scope, &names);
+
+    write_comment(proto, indent, message.description);
+    writeln!(proto, "{indent}message {} {{", message.name).unwrap();
+    let inner = format!("{indent}  ");
+    if !reserved.is_empty() {
+        let reserved: Vec<_> = reserved.iter().map(u32::to_string).collect();
+        writeln!(proto, "{inner}reserved {};", reserved.join(", ")).unwrap();
+    }
+
+    let mut nested = String::new();
+    for (name, number) in assigned {
+        let Some(property) = message
+            .properties
+            .iter()
+            .find(|p| to_snake_case(&p.name) == name)
+        else {
+            continue;
+        };
+
+        let mut description = one_line(property.description.as_deref().unwrap_or_default());
+        if let Some(units) = property.units() {
+            write!(description, " Units: {units}").unwrap();
+        }
+        write_comment(proto, &inner, &description);
+
+        let field = FieldType::from_property(property);
+        let (label, typ) = match &field {
+            FieldType::Array(item) => {
+                let item_property = Property {
+                    name: property.name.clone(),
+                    ..property.item().unwrap_or_default()
+                };
+                let typ = match item.as_ref() {
+                    FieldType::Array(_) => {
+                        imports.insert("google/protobuf/struct.proto");
+                        String::from("google.protobuf.ListValue")
+                    }
+                    item => proto_type(
+                        &item_property,
+                        item,
+                        &scope,
+                        numbers,
+                        imports,
+                        &mut nested,
+                        &inner,
+                    ),
+                };
+                ("repeated ", typ)
+            }
+            _ => {
+                let typ = proto_type(
+                    property,
+                    &field,
+                    &scope,
+                    numbers,
+                    imports,
+                    &mut nested,
+                    &inner,
+                );
+                // Scalars and enums need `optional` to tell a missing value from its default
+                let has_presence = matches!(field, FieldType::Object | FieldType::Geo(_))
+                    || typ.starts_with("google.");
+                let label = if property.required || has_presence {
+                    ""
+                } else {
+                    "optional "
+                };
+                (label, typ)
+            }
+        };
+
+        write!(proto, "{inner}{label}{typ} {name} = {number}").unwrap();
+        if json_name(&name) != property.name {
+            write!(proto, " [json_name = \"{}\"]", property.name).unwrap();
+        }
+        writeln!(proto, ";").unwrap();
+    }
+
+    if !nested.is_empty() {
+        writeln!(proto).unwrap();
+        proto.push_str(nested.trim_end());
+        writeln!(proto).unwrap();
+    }
+    writeln!(proto, "{indent}}}").unwrap();
+}
+
+/// The type of a single value, nested enums and messages are written to `nested`.
+fn proto_type(
+    property: &Property,
+    field: &FieldType,
+    scope: &str,
+    numbers: &Numbers,
+    imports: &mut BTreeSet<&'static str>,
+    nested: &mut String,
+    indent: &str,
+) -> String {
+    match field {
+        FieldType::String
+        | FieldType::Uri
+        | FieldType::Relationship(_)
+        | FieldType::Date
+        | FieldType::Time => String::from("string"),
+        FieldType::Integer => String::from("int64"),
+        FieldType::Number => String::from("double"),
+        FieldType::Boolean => String::from("bool"),
+        FieldType::DateTime => {
+            imports.insert("google/protobuf/timestamp.proto");
+            String::from("google.protobuf.Timestamp")
+        }
+        FieldType::Enum(values) => {
+            let name = to_pascal_case(&property.name);
+            write_enum(nested, &join(scope, &name), &name, values, numbers, indent);
+            name
+        }
+        FieldType::Object if !property.members().is_empty() => {
+            let name = to_pascal_case(&property.name);
+            let members = property.members();
+            let message = Message {
+                name: &name,
+                description: property.description.as_deref().unwrap_or_default(),
+                properties: &members,
+                scope,
+            };
+            write_message(nested, &message, numbers, imports, indent);
+            name
+        }
+        FieldType::Geo(_) | FieldType::Object | FieldType::Array(_) => {
+            imports.insert("google/protobuf/struct.proto");
+            String::from("google.protobuf.Struct")
+        }
+    }
+}
+
+/// Enum values are prefixed with the enum name, as they share the scope of the message.
+fn write_enum(
+    proto: &mut String,
+    scope: &str,
+    name: &str,
+    values: &[String],
+    numbers: &Numbers,
+    indent: &str,
+) {
+    let prefix = to_screaming_snake_case(name);
+    let names = unique_names(
+        values
+            .iter()
+            .map(|value| format!("{prefix}_{}", to_screaming_snake_case(value))),
+    );
+    let (assigned, reserved) = numbers.assign(scope, &names);
+
+    writeln!(proto, "{indent}enum {name} {{").unwrap();
+    if !reserved.is_empty() {
+        let reserved: Vec<_> = reserved.iter().map(u32::to_string).collect();
+        writeln!(proto, "{indent}  reserved {};", reserved.join(", ")).unwrap();
+    }
+    writeln!(proto, "{indent}  {prefix}_UNSPECIFIED = 0;").unwrap();
+    for (value, number) in assigned {
+        writeln!(proto, "{indent}  {value} = {number};").unwrap();
+    }
+    writeln!(proto, "{indent}}}").unwrap();
+    writeln!(proto).unwrap();
+}
+
+fn write_comment(proto: &mut String, indent: &str, text: &str) {
+    let text = one_line(text);
+    if !text.is_empty() {
+        writeln!(proto, "{indent}// {text}").unwrap();
+    }
+}
+
+fn join(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+/// The json name protoc derives from a field name, e.g. `date_observed` becomes `dateObserved`.
+fn json_name(name: &str) -> String {
+    let mut json_name = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            json_name.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            json_name.push(c);
+        }
+    }
+    json_name
+}
+
+/// Field and enum value numbers of a previously generated file, by scope.
+#[derive(Debug, Default)]
+struct Numbers {
+    /// Numbers by scope and name, e.g. `Building.Address` and `street_address`.
+    assigned: HashMap<String, Vec<(String, u32)>>,
+    reserved: HashMap<String, Vec<u32>>,
+}
+
+impl Numbers {
+    fn parse(proto: &str) -> Self {
+        let mut numbers = Numbers::default();
+        let mut scope: Vec<&str> = vec![];
+
+        for line in proto.lines().map(str::trim) {
+            if line.starts_with("//") {
+                continue;
+            }
+            if let Some(name) = line
+                .strip_prefix("message ")
+                .or_else(|| line.strip_prefix("enum "))
+                .and_then(|rest| rest.strip_suffix('{'))
+            {
+                scope.push(name.trim());
+            } else if line == "}" {
+                scope.pop();
+            } else if let Some(reserved) = line.strip_prefix("reserved ") {
+                numbers.reserved.entry(scope.join(".")).or_default().extend(
+                    reserved
+                        .trim_end_matches(';')
+                        .split(',')
+                        .filter_map(|n| n.trim().parse::<u32>().ok()),
+                );
+            } else if let Some((declaration, number)) = line.split_once(" = ") {
+                let name = declaration.split_whitespace().last().unwrap_or_default();
+                let number = number
+                    .split(|c: char| !c.is_ascii_digit())
+                    .next()
+                    .and_then(|n| n.parse::<u32>().ok());
+                if let Some(number) = number.filter(|&n| n > 0) {
+                    numbers
+                        .assigned
+                        .entry(scope.join("."))
+                        .or_default()
+                        .push((name.to_string(), number));
+                }
+            }
+        }
+        numbers
+    }
+
+    /// Numbers `names` in `scope`, previous numbers are kept and new names get unused numbers.
+    /// Returns the names ordered by number and the numbers which must not be used again.
+    fn assign(&self, scope: &str, names: &[String]) -> (Vec<(String, u32)>, Vec<u32>) {
+        let previous = self.assigned.get(scope).cloned().unwrap_or_default();
+        let mut reserved = self.reserved.get(scope).cloned().unwrap_or_default();
+        reserved.extend(
+            previous
+                .iter()
+                .filter(|(name, _)| !names.contains(name))
+                .map(|(_, number)| *number),
+        );
+        reserved.sort_unstable();
+        reserved.dedup();
+
+        let mut next = previous
+            .iter()
+            .map(|(_, number)| *number)
+            .chain(reserved.iter().copied())
+            .max()
+            .map_or(1, |max| max + 1);
+
+        let mut assigned: Vec<_> = names
+            .iter()
+            .map(|name| {
+                let number = match previous.iter().find(|(n, _)| n == name) {
+                    Some((_, number)) => *number,
+                    None => {
+                        let number = next;
+                        next += 1;
+                        number
+                    }
+                };
+                (name.clone(), number)
+            })
+            .collect();
+        assigned.sort_by_key(|(_, number)| *number);
+
+        (assigned, reserved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::generate;
+
+    fn model(properties: Vec<(&str, &str, bool)>) -> ParsedModel {
+        ParsedModel {
+            name: "Building".to_string(),
+            properties: properties
+                .into_iter()
+                .map(|(name, yaml, required)| Property {
+                    required,
+                    ..Property::from_yaml(name, yaml)
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn create_message() {
+        let proto = "syntax = \"proto3\";\n\n\
+            import \"google/protobuf/timestamp.proto\";\n\n\
+            message Building {\n  \
+            string id = 1;\n  \
+            optional Category category = 2;\n  \
+            google.protobuf.Timestamp date_created = 3;\n  \
+            optional double co2 = 4 [json_name = \"CO2\"];\n\n  \
+            enum Category {\n    \
+            CATEGORY_UNSPECIFIED = 0;\n    \
+            CATEGORY_OFFICE = 1;\n    \
+            CATEGORY_NON_RESIDENTIAL = 2;\n    \
+            CATEGORY_NON_RESIDENTIAL_2 = 3;\n  \
+            }\n\
+            }\n";
+
+        let model = model(vec![
+            ("id", "{type: string}", true),
+            (
+                "category",
+                "{type: string, enum: [office, non-residential, non_residential]}",
+                false,
+            ),
+            ("dateCreated", "{type: string, format: date-time}", false),
+            ("CO2", "{type: number}", false),
+        ]);
+
+        assert_eq!(proto, generate(&model, None));
+    }
+
+    #[test]
+    fn keep_previous_numbers() {
+        let previous = generate(
+            &model(vec![
+                ("id", "{type: string}", true),
+                ("name", "{type: string}", false),
+                ("floors", "{type: integer}", false),
+            ]),
+            None,
+        );
+
+        let proto = generate(
+            &model(vec![
+                ("address", "{type: string}", false),
+                ("floors", "{type: integer}", false),
+                ("id", "{type: string}", true),
+            ]),
+            Some(&previous),
+        );
+
+        assert!(proto.contains(
+            "  reserved 2;\n  string id = 1;\n  optional int64 floors = 3;\n  optional string address = 4;\n"
+        ));
+    }
+}
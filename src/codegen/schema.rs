@@ -0,0 +1,65 @@
+use serde_json::{json, Map, Value};
+
+use crate::smartdata::models::Property;
+
+/// The JSON Schema keywords of a property, as far as the parsed model knows them.
+pub fn property_schema(property: &Property) -> Map<String, Value> {
+    let mut schema = Map::new();
+    if let Some(description) = &property.description {
+        schema.insert("description".to_string(), json!(description));
+    }
+    if let Some(typ) = &property.typ {
+        schema.insert("type".to_string(), json!(typ));
+    }
+    if let Some(format) = &property.format {
+        schema.insert("format".to_string(), json!(format));
+    }
+    if let Some(enums) = &property.enums {
+        schema.insert("enum".to_string(), json!(enums));
+    }
+    if let Some(minimum) = property.minimum {
+        schema.insert("minimum".to_string(), number(minimum));
+    }
+    if let Some(maximum) = property.maximum {
+        schema.insert("maximum".to_string(), number(maximum));
+    }
+    if let Some(min_length) = property.min_length {
+        schema.insert("minLength".to_string(), json!(min_length));
+    }
+    if let Some(max_length) = property.max_length {
+        schema.insert("maxLength".to_string(), json!(max_length));
+    }
+    if let Some(pattern) = &property.pattern {
+        schema.insert("pattern".to_string(), json!(pattern));
+    }
+
+    let nested = [
+        ("anyOf", property.any_of.as_ref().map(|v| json!(v))),
+        ("oneOf", property.one_of.as_ref().map(|v| json!(v))),
+        ("items", property.items.as_ref().map(|v| json!(v))),
+        ("properties", property.properties.as_ref().map(|v| json!(v))),
+        (
+            "required",
+            property.required_members.as_ref().map(|v| json!(v)),
+        ),
+    ];
+    for (keyword, value) in nested {
+        if let Some(value) = value {
+            schema.insert(keyword.to_string(), value);
+        }
+    }
+
+    if let Some(xngsi) = &property.xngsi {
+        schema.insert("x-ngsi".to_string(), json!(xngsi));
+    }
+    schema
+}
+
+/// Whole numbers are written without a fraction, like in the upstream schemas.
+pub fn number(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        json!(value as i64)
+    } else {
+        json!(value)
+    }
+}
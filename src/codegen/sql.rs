@@ -0,0 +1,252 @@
+use std::fmt::Write;
+
+use crate::{
+    codegen::{case::to_snake_case, one_line, types::FieldType},
+    smartdata::models::{ParsedModel, Property},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dialect {
+    Postgres,
+    /// PostgreSQL with a hypertable of the entity history.
+    Timescale,
+    /// The table layout QuantumLeap writes to CrateDB.
+    CrateDb,
+}
+
+/// Generates a `CREATE TABLE` statement with a column for every checked property.
+pub fn generate(model: &ParsedModel, dialect: Dialect) -> String {
+    let properties: Vec<_> = model
+        .properties
+        .iter()
+        .filter(|p| p.checked && p.name != "id" && p.name != "type")
+        .collect();
+    let has_geo = properties
+        .iter()
+        .any(|p| matches!(FieldType::from_property(p), FieldType::Geo(_)));
+
+    let mut sql = String::new();
+    writeln!(sql, "-- {}", one_line(&model.description)).unwrap();
+    if dialect == Dialect::Timescale {
+        writeln!(sql, "CREATE EXTENSION IF NOT EXISTS timescaledb;").unwrap();
+    }
+    if has_geo && dialect != Dialect::CrateDb {
+        writeln!(sql, "CREATE EXTENSION IF NOT EXISTS postgis;").unwrap();
+    }
+    writeln!(sql).unwrap();
+
+    let table = table_name(model, dialect);
+    writeln!(sql, "CREATE TABLE IF NOT EXISTS {table} (").unwrap();
+
+    let mut columns: Vec<String> = match dialect {
+        Dialect::Postgres => vec![String::from("id TEXT PRIMARY KEY")],
+        Dialect::Timescale => vec![
+            String::from("time_index TIMESTAMPTZ NOT NULL"),
+            String::from("id TEXT NOT NULL"),
+        ],
+        // QuantumLeap adds these to every entity table
+        Dialect::CrateDb => vec![
+            String::from("entity_id TEXT NOT NULL"),
+            String::from("entity_type TEXT"),
+            String::from("time_index TIMESTAMP WITH TIME ZONE NOT NULL"),
+            String::from("fiware_servicepath TEXT"),
+            String::from("__original_ngsi_entity__ OBJECT(IGNORED)"),
+            String::from("instanceid TEXT"),
+        ],
+    };
+    columns.extend(properties.iter().map(|p| column(p, dialect)));
+
+    let last = columns.len() - 1;
+    for (i, column) in columns.iter().enumerate() {
+        let separator = if i == last { "" } else { "," };
+        writeln!(sql, "    {column}{separator}").unwrap();
+    }
+
+    match dialect {
+        Dialect::Postgres => writeln!(sql, ");").unwrap(),
+        Dialect::Timescale => {
+            writeln!(sql, ");").unwrap();
+            writeln!(
+                sql,
+                "SELECT create_hypertable('{table}', 'time_index', if_not_exists => TRUE);"
+            )
+            .unwrap();
+        }
+        Dialect::CrateDb => writeln!(sql, ") WITH (column_policy = 'dynamic');").unwrap(),
+    }
+    sql
+}
+
+/// QuantumLeap names tables `et<type>` in the `doc` schema, or `mt<tenant>` with a tenant.
+fn table_name(model: &ParsedModel, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::Postgres | Dialect::Timescale => to_snake_case(&model.name),
+        Dialect::CrateDb => format!("\"doc\".\"et{}\"", model.name.to_lowercase()),
+    }
+}
+
+fn column_name(property: &Property, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::Postgres | Dialect::Timescale => format!("\"{}\"", to_snake_case(&property.name)),
+        // QuantumLeap lowercases attribute names
+        Dialect::CrateDb => format!("\"{}\"", property.name.to_lowercase()),
+    }
+}
+
+/// The column definition, preceded by a comment with the description and units.
+fn column(property: &Property, dialect: Dialect) -> String {
+    let field = FieldType::from_property(property);
+    let name = column_name(property, dialect);
+
+    let mut column = String::new();
+    let mut comment = one_line(property.description.as_deref().unwrap_or_default());
+    if let Some(units) = property.units() {
+        write!(comment, " [{units}]").unwrap();
+    }
+    if !comment.trim().is_empty() {
+        write!(column, "-- {}\n    ", comment.trim()).unwrap();
+    }
+
+    write!(column, "{name} {}", column_type(&field, property, dialect)).unwrap();
+    // QuantumLeap inserts notifications of the changed attributes only, so any may be missing
+    if property.required && dialect != Dialect::CrateDb {
+        write!(column, " NOT NULL").unwrap();
+    }
+
+    let checks = checks(&name, &field, property);
+    if !checks.is_empty() {
+        write!(column, " CHECK ({})", checks.join(" AND ")).unwrap();
+    }
+    column
+}
+
+fn column_type(field: &FieldType, property: &Property, dialect: Dialect) -> String {
+    let postgres = dialect != Dialect::CrateDb;
+    let typ = match field {
+        FieldType::String | FieldType::Enum(_) => match property.max_length {
+            Some(max_length) => return format!("VARCHAR({max_length})"),
+            None => "TEXT",
+        },
+        FieldType::Uri | FieldType::Relationship(_) => "TEXT",
+        FieldType::Integer => "BIGINT",
+        FieldType::Number if postgres => "DOUBLE PRECISION",
+        FieldType::Number => "REAL",
+        FieldType::Boolean => "BOOLEAN",
+        FieldType::DateTime if postgres => "TIMESTAMPTZ",
+        FieldType::DateTime => "TIMESTAMP WITH TIME ZONE",
+        FieldType::Date if postgres => "DATE",
+        FieldType::Time if postgres => "TIME",
+        // CrateDB can not store dates and times of day on their own
+        FieldType::Date | FieldType::Time => "TEXT",
+        FieldType::Geo(variant) if postgres => {
+            return format!(
+                "geometry({}, 4326)",
+                variant.as_deref().unwrap_or("Geometry")
+            )
+        }
+        FieldType::Geo(_) => "GEO_SHAPE",
+        FieldType::Object if postgres => "JSONB",
+        FieldType::Object => "OBJECT(DYNAMIC)",
+        FieldType::Array(item) if !item.is_scalar() && postgres => "JSONB",
+        FieldType::Array(item) if !item.is_scalar() => "ARRAY(OBJECT(DYNAMIC))",
+        FieldType::Array(item) => {
+            let item_type = column_type(item, &Property::default(), dialect);
+            return if postgres {
+                format!("{item_type}[]")
+            } else {
+                format!("ARRAY({item_type})")
+            };
+        }
+    };
+    typ.to_string()
+}
+
+fn checks(name: &str, field: &FieldType, property: &Property) -> Vec<String> {
+    let mut checks = vec![];
+    if let FieldType::Enum(enums) = field {
+        let values: Vec<_> = enums.iter().map(|value| quote(value)).collect();
+        checks.push(format!("{name} IN ({})", values.join(", ")));
+    }
+    if let Some(minimum) = property.minimum {
+        checks.push(format!("{name} >= {minimum}"));
+    }
+    if let Some(maximum) = property.maximum {
+        checks.push(format!("{name} <= {maximum}"));
+    }
+    if let Some(min_length) = property.min_length.filter(|&n| n > 0) {
+        checks.push(format!("char_length({name}) >= {min_length}"));
+    }
+    if let Some(pattern) = &property.pattern {
+        checks.push(format!("{name} ~ {}", quote(pattern)));
+    }
+    checks
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property, XNgsi};
+
+    use super::{generate, Dialect};
+
+    fn model() -> ParsedModel {
+        ParsedModel {
+            name: "WeatherObserved".to_string(),
+            description: "Weather observed at a place.".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property {
+                    required: true,
+                    ..Property::from_yaml("dateObserved", "{type: string, format: date-time}")
+                },
+                Property::from_yaml(
+                    "temperature",
+                    "{type: number, minimum: -100, description: Air temperature}",
+                ),
+                Property::from_yaml("status", "{type: string, enum: [ok, 'n''a']}"),
+                Property {
+                    variant: Some("Point".to_string()),
+                    xngsi: Some(XNgsi {
+                        typ: Some("GeoProperty".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Property::from_yaml("location", "{oneOf: [{type: object}]}")
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn postgres_table() {
+        let sql = "-- Weather observed at a place.\n\
+            CREATE EXTENSION IF NOT EXISTS postgis;\n\n\
+            CREATE TABLE IF NOT EXISTS weather_observed (\n    \
+            id TEXT PRIMARY KEY,\n    \
+            \"date_observed\" TIMESTAMPTZ NOT NULL,\n    \
+            -- Air temperature\n    \
+            \"temperature\" DOUBLE PRECISION CHECK (\"temperature\" >= -100),\n    \
+            \"status\" TEXT CHECK (\"status\" IN ('ok', 'n''a')),\n    \
+            \"location\" geometry(Point, 4326)\n\
+            );\n";
+
+        assert_eq!(sql, generate(&model(), Dialect::Postgres));
+    }
+
+    #[test]
+    fn quantumleap_table() {
+        let sql = generate(&model(), Dialect::CrateDb);
+
+        assert!(sql.contains("CREATE TABLE IF NOT EXISTS \"doc\".\"etweatherobserved\" ("));
+        assert!(sql.contains("    entity_id TEXT NOT NULL,\n"));
+        assert!(sql.contains("    \"dateobserved\" TIMESTAMP WITH TIME ZONE,\n"));
+        assert!(sql.contains("    \"location\" GEO_SHAPE\n"));
+        assert!(!sql.contains("postgis"));
+    }
+}
@@ -0,0 +1,225 @@
+//! Code generation with user templates in the Jinja syntax of `minijinja`.
+//!
+//! Every file in the templates directory is a template, e.g. `pydantic.py.j2`, which is listed
+//! as `pydantic.py`. Templates are rendered with:
+//!
+//! - `model`: `name`, `repo`, `description`, `version`, `git_ref` and `required`, the names of
+//!   the required properties
+//! - `properties`: all properties of the model, `checked_properties`: only the checked ones
+//!
+//! Each property has `name`, `description`, `checked`, `required`, `kind` (`Property`,
+//! `GeoProperty` or `Relationship`), `type` (see `FieldType::str`), `json_types`, `format`,
+//! `enums`, `units`, `target` (the entity type of relationships), `item_type` (the `type` of
+//! array items) and `constraints`, a map like `{"minimum": "0"}`.
+//!
+//! The filters `snake_case`, `camel_case`, `pascal_case`, `kebab_case` and
+//! `screaming_snake_case` convert names, e.g. `{{ model.name | snake_case }}`.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use minijinja::Environment;
+use serde::Serialize;
+
+use crate::{
+    codegen::{case, types::FieldType},
+    config::config_dir,
+    smartdata::models::{ParsedModel, Property},
+};
+
+const SUFFIXES: [&str; 2] = [".j2", ".jinja"];
+
+pub fn templates_dir() -> PathBuf {
+    config_dir().join("templates")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    /// The file name without the template suffix, e.g. `pydantic.py`.
+    pub name: String,
+    pub source: String,
+}
+
+impl Template {
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow::anyhow!("invalid template path {}", path.display()))?;
+        let name = SUFFIXES
+            .iter()
+            .find_map(|suffix| file_name.strip_suffix(suffix))
+            .unwrap_or(file_name);
+
+        Ok(Template {
+            name: name.to_string(),
+            source: fs::read_to_string(path)?,
+        })
+    }
+
+    /// All templates of the templates directory, sorted by name.
+    pub fn list_saved() -> Vec<Template> {
+        let Ok(entries) = fs::read_dir(templates_dir()) else {
+            return vec![];
+        };
+
+        let mut templates: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter_map(|path| Template::load(&path).ok())
+            .collect();
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        templates
+    }
+
+    pub fn render(&self, model: &ParsedModel) -> Result<String, anyhow::Error> {
+        let mut env = Environment::new();
+        env.set_keep_trailing_newline(true);
+        env.add_filter("snake_case", |name: &str| case::to_snake_case(name));
+        env.add_filter("camel_case", |name: &str| case::to_camel_case(name));
+        env.add_filter("pascal_case", |name: &str| case::to_pascal_case(name));
+        env.add_filter("kebab_case", |name: &str| case::to_kebab_case(name));
+        env.add_filter("screaming_snake_case", |name: &str| {
+            case::to_screaming_snake_case(name)
+        });
+        env.add_template(&self.name, &self.source)?;
+
+        let rendered = env
+            .get_template(&self.name)?
+            .render(TemplateContext::new(model))?;
+        Ok(rendered)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TemplateContext {
+    model: ModelContext,
+    properties: Vec<PropertyContext>,
+    checked_properties: Vec<PropertyContext>,
+}
+
+#[derive(Debug, Serialize)]
+struct ModelContext {
+    name: String,
+    repo: String,
+    description: String,
+    version: String,
+    git_ref: String,
+    required: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PropertyContext {
+    name: String,
+    description: String,
+    checked: bool,
+    required: bool,
+    kind: &'static str,
+    #[serde(rename = "type")]
+    typ: &'static str,
+    json_types: Vec<String>,
+    format: Option<String>,
+    enums: Vec<String>,
+    units: Option<String>,
+    target: Option<String>,
+    item_type: Option<&'static str>,
+    constraints: BTreeMap<&'static str, String>,
+}
+
+impl TemplateContext {
+    fn new(model: &ParsedModel) -> Self {
+        let properties: Vec<_> = model.properties.iter().map(PropertyContext::new).collect();
+        let checked_properties = properties.iter().filter(|p| p.checked).cloned().collect();
+
+        TemplateContext {
+            model: ModelContext {
+                name: model.name.clone(),
+                repo: model.repo.clone(),
+                description: model.description.clone(),
+                version: model.version.clone(),
+                git_ref: model.git_ref.clone(),
+                required: model.required.clone(),
+            },
+            properties,
+            checked_properties,
+        }
+    }
+}
+
+impl PropertyContext {
+    fn new(property: &Property) -> Self {
+        let field = FieldType::from_property(property);
+        let enums = match &field {
+            FieldType::Enum(values) => values.clone(),
+            _ => vec![],
+        };
+        let target = match &field {
+            FieldType::Relationship(target) => Some(target.clone()),
+            _ => None,
+        };
+        let item_type = match &field {
+            FieldType::Array(item) => Some(item.str()),
+            _ => None,
+        };
+
+        PropertyContext {
+            name: property.name.clone(),
+            description: property.description.clone().unwrap_or_default(),
+            checked: property.checked,
+            required: property.required,
+            kind: field.ngsi_kind(),
+            typ: field.str(),
+            json_types: property
+                .json_types()
+                .iter()
+                .map(|t| t.to_string())
+                .collect(),
+            format: property.format.clone(),
+            enums,
+            units: property.units().map(str::to_string),
+            target,
+            item_type,
+            constraints: property.constraints().into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::Template;
+
+    #[test]
+    fn render_template() {
+        let template = Template {
+            name: "model.py".to_string(),
+            source: "class {{ model.name | pascal_case }}:\n\
+                {%- for p in checked_properties %}\n    \
+                {{ p.name | snake_case }}: {{ p.type }}{% if p.units %}  # {{ p.units }}{% endif %}\n\
+                {%- endfor %}\n"
+                .to_string(),
+        };
+        let model = ParsedModel {
+            name: "WeatherObserved".to_string(),
+            properties: vec![
+                Property::from_yaml("dateObserved", "{type: string, format: date-time}"),
+                Property::from_yaml("temperature", "{type: number, x-ngsi: {units: celsius}}"),
+                Property {
+                    checked: false,
+                    ..Property::from_yaml("pressure", "{type: number}")
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            template.render(&model).unwrap(),
+            "class WeatherObserved:\n    date_observed: date-time\n    temperature: number  # celsius\n"
+        );
+    }
+}
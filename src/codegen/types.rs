@@ -0,0 +1,132 @@
+use crate::smartdata::{graph::relationship_target, models::Property};
+
+/// A language neutral classification of a property, which every generator maps to its own types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    DateTime,
+    Date,
+    Time,
+    Uri,
+    Enum(Vec<String>),
+    /// A GeoJSON geometry, with the chosen geometry type if there is one.
+    Geo(Option<String>),
+    /// A relationship to the entity type it names.
+    Relationship(String),
+    Object,
+    Array(Box<FieldType>),
+}
+
+impl FieldType {
+    pub fn from_property(property: &Property) -> Self {
+        if property.is_geo() {
+            return FieldType::Geo(property.variant.clone());
+        }
+        if let Some(target) = relationship_target(property) {
+            return FieldType::Relationship(target);
+        }
+        if let Some(enums) = &property.enums {
+            return FieldType::Enum(enums.clone());
+        }
+
+        match property.json_types().as_slice() {
+            ["string"] => match property.format.as_deref() {
+                Some("date-time") => FieldType::DateTime,
+                Some("date") => FieldType::Date,
+                Some("time") => FieldType::Time,
+                Some("uri") | Some("uri-reference") | Some("iri") => FieldType::Uri,
+                _ => FieldType::String,
+            },
+            ["integer"] => FieldType::Integer,
+            ["number"] | ["integer", "number"] | ["number", "integer"] => FieldType::Number,
+            ["boolean"] => FieldType::Boolean,
+            ["array"] => {
+                let item = property
+                    .item()
+                    .map(|item| FieldType::from_property(&item))
+                    .unwrap_or(FieldType::Object);
+                FieldType::Array(Box::new(item))
+            }
+            // Everything without a single type, like `anyOf` string or uri ids, is kept as text
+            ["string", ..] if !property.json_types().contains(&"object") => FieldType::String,
+            _ => FieldType::Object,
+        }
+    }
+
+    /// The name of the classification, e.g. for templates.
+    pub fn str(&self) -> &'static str {
+        match self {
+            FieldType::String => "string",
+            FieldType::Integer => "integer",
+            FieldType::Number => "number",
+            FieldType::Boolean => "boolean",
+            FieldType::DateTime => "date-time",
+            FieldType::Date => "date",
+            FieldType::Time => "time",
+            FieldType::Uri => "uri",
+            FieldType::Enum(_) => "enum",
+            FieldType::Geo(_) => "geo",
+            FieldType::Relationship(_) => "relationship",
+            FieldType::Object => "object",
+            FieldType::Array(_) => "array",
+        }
+    }
+
+    /// The NGSI-LD attribute kind values of this type are wrapped in.
+    pub fn ngsi_kind(&self) -> &'static str {
+        match self {
+            FieldType::Geo(_) => "GeoProperty",
+            FieldType::Relationship(_) => "Relationship",
+            _ => "Property",
+        }
+    }
+
+    /// Whether the value is a single json scalar.
+    pub fn is_scalar(&self) -> bool {
+        !matches!(
+            self,
+            FieldType::Geo(_) | FieldType::Object | FieldType::Array(_)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::Property;
+
+    use super::FieldType;
+
+    #[test]
+    fn classify_properties() {
+        let property =
+            |yaml: &str, name: &str| FieldType::from_property(&Property::from_yaml(name, yaml));
+
+        assert_eq!(
+            property("{type: string, format: date-time}", "dateObserved"),
+            FieldType::DateTime
+        );
+        assert_eq!(
+            property("{type: string, enum: [active, inactive]}", "status"),
+            FieldType::Enum(vec!["active".to_string(), "inactive".to_string()])
+        );
+        assert_eq!(
+            property(
+                "{anyOf: [{type: string}, {type: string, format: uri}]}",
+                "refDevice"
+            ),
+            FieldType::Relationship("Device".to_string())
+        );
+        assert_eq!(
+            property("{type: array, items: {type: number}}", "values"),
+            FieldType::Array(Box::new(FieldType::Number))
+        );
+        assert_eq!(
+            property("{oneOf: [{type: object}]}", "location"),
+            FieldType::Geo(None)
+        );
+        assert_eq!(property("{type: object}", "address"), FieldType::Object);
+    }
+}
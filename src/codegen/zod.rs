@@ -0,0 +1,219 @@
+use std::fmt::Write;
+
+use crate::{
+    codegen::{one_line, types::FieldType},
+    smartdata::models::{GeoProperty, ParsedModel, Property},
+};
+
+/// Generates a TypeScript module with a Zod schema of the entity and its inferred type.
+pub fn generate(model: &ParsedModel) -> String {
+    let properties: Vec<_> = model
+        .properties
+        .iter()
+        .filter(|p| p.checked)
+        .cloned()
+        .collect();
+    let uses_geometry = properties.iter().any(uses_geometry);
+
+    let mut ts = String::new();
+    writeln!(ts, "// Generated from the smart data model {}.", model.name).unwrap();
+    writeln!(ts).unwrap();
+    writeln!(ts, "import {{ z }} from \"zod\";").unwrap();
+    writeln!(ts).unwrap();
+    if uses_geometry {
+        let types: Vec<_> = GeoProperty::array()
+            .iter()
+            .map(|geo| format!("\"{}\"", geo.str()))
+            .collect();
+        writeln!(ts, "const geometry = z.object({{").unwrap();
+        writeln!(ts, "  type: z.enum([{}]),", types.join(", ")).unwrap();
+        writeln!(ts, "  coordinates: z.array(z.any()),").unwrap();
+        writeln!(ts, "}});").unwrap();
+        writeln!(ts).unwrap();
+    }
+    if !model.description.is_empty() {
+        writeln!(ts, "/** {} */", one_line(&model.description)).unwrap();
+    }
+    writeln!(
+        ts,
+        "export const {}Schema = {};",
+        model.name,
+        object(&properties, "")
+    )
+    .unwrap();
+    writeln!(ts).unwrap();
+    writeln!(
+        ts,
+        "export type {0} = z.infer<typeof {0}Schema>;",
+        model.name
+    )
+    .unwrap();
+    ts
+}
+
+fn object(properties: &[Property], indent: &str) -> String {
+    let inner = format!("{indent}  ");
+    let mut ts = String::from("z.object({\n");
+    for property in properties {
+        let mut schema = schema(property, &FieldType::from_property(property), &inner);
+        if let Some(description) = &property.description {
+            let description = serde_json::to_string(&one_line(description)).unwrap();
+            write!(schema, ".describe({description})").unwrap();
+        }
+        if !property.required {
+            schema.push_str(".optional()");
+        }
+        writeln!(ts, "{inner}{}: {schema},", key(&property.name)).unwrap();
+    }
+    write!(ts, "{indent}}})").unwrap();
+    ts
+}
+
+fn schema(property: &Property, field: &FieldType, indent: &str) -> String {
+    let mut schema = match field {
+        FieldType::String | FieldType::Relationship(_) => String::from("z.string()"),
+        FieldType::Integer => String::from("z.number().int()"),
+        FieldType::Number => String::from("z.number()"),
+        FieldType::Boolean => String::from("z.boolean()"),
+        FieldType::DateTime => String::from("z.string().datetime({ offset: true })"),
+        FieldType::Date => String::from("z.string().date()"),
+        FieldType::Time => String::from("z.string().time()"),
+        FieldType::Uri => String::from("z.string().url()"),
+        FieldType::Enum(values) if values.is_empty() => String::from("z.string()"),
+        FieldType::Enum(values) => {
+            let values: Vec<_> = values
+                .iter()
+                .map(|value| serde_json::to_string(value).unwrap())
+                .collect();
+            format!("z.enum([{}])", values.join(", "))
+        }
+        FieldType::Geo(Some(variant)) => {
+            format!("geometry.extend({{ type: z.literal(\"{variant}\") }})")
+        }
+        FieldType::Geo(None) => String::from("geometry"),
+        FieldType::Object => {
+            let members = property.members();
+            if members.is_empty() {
+                String::from("z.record(z.string(), z.unknown())")
+            } else {
+                object(&members, indent)
+            }
+        }
+        FieldType::Array(item) => {
+            let item_property = Property {
+                name: property.name.clone(),
+                ..property.item().unwrap_or_default()
+            };
+            format!("z.array({})", schema(&item_property, item, indent))
+        }
+    };
+
+    match field {
+        FieldType::String => {
+            match property.format.as_deref() {
+                Some("email") => schema.push_str(".email()"),
+                Some("uuid") => schema.push_str(".uuid()"),
+                _ => {}
+            }
+            if let Some(min_length) = property.min_length {
+                write!(schema, ".min({min_length})").unwrap();
+            }
+            if let Some(max_length) = property.max_length {
+                write!(schema, ".max({max_length})").unwrap();
+            }
+            if let Some(pattern) = &property.pattern {
+                let pattern = serde_json::to_string(pattern).unwrap();
+                write!(schema, ".regex(new RegExp({pattern}))").unwrap();
+            }
+        }
+        FieldType::Integer | FieldType::Number => {
+            if let Some(minimum) = property.minimum {
+                write!(schema, ".min({minimum})").unwrap();
+            }
+            if let Some(maximum) = property.maximum {
+                write!(schema, ".max({maximum})").unwrap();
+            }
+        }
+        _ => {}
+    }
+    schema
+}
+
+fn uses_geometry(property: &Property) -> bool {
+    match FieldType::from_property(property) {
+        FieldType::Geo(_) => true,
+        FieldType::Object => property.members().iter().any(uses_geometry),
+        FieldType::Array(_) => property.item().is_some_and(|item| uses_geometry(&item)),
+        _ => false,
+    }
+}
+
+/// Names which are no identifiers are quoted.
+fn key(name: &str) -> String {
+    let is_identifier = !name.starts_with(|c: char| c.is_ascii_digit())
+        && !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        serde_json::to_string(name).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::generate;
+
+    #[test]
+    fn create_schema() {
+        let ts = "// Generated from the smart data model Device.\n\n\
+            import { z } from \"zod\";\n\n\
+            const geometry = z.object({\n  \
+            type: z.enum([\"Point\", \"LineString\", \"Polygon\", \"MultiPoint\", \"MultiLineString\", \"MultiPolygon\"]),\n  \
+            coordinates: z.array(z.any()),\n\
+            });\n\n\
+            export const DeviceSchema = z.object({\n  \
+            id: z.string(),\n  \
+            batteryLevel: z.number().min(0).max(1).describe(\"Battery level.\").optional(),\n  \
+            dateCreated: z.string().datetime({ offset: true }).optional(),\n  \
+            location: geometry.extend({ type: z.literal(\"Point\") }).optional(),\n  \
+            status: z.enum([\"ok\", \"outOfService\"]).optional(),\n  \
+            address: z.object({\n    \
+            streetAddress: z.string().max(80),\n  \
+            }).optional(),\n\
+            });\n\n\
+            export type Device = z.infer<typeof DeviceSchema>;\n";
+
+        let model = ParsedModel {
+            name: "Device".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml(
+                    "batteryLevel",
+                    "{type: number, minimum: 0, maximum: 1, description: Battery level.}",
+                ),
+                Property::from_yaml("dateCreated", "{type: string, format: date-time}"),
+                Property {
+                    variant: Some("Point".to_string()),
+                    ..Property::from_yaml("location", "{}")
+                },
+                Property::from_yaml("status", "{type: string, enum: [ok, outOfService]}"),
+                Property::from_yaml(
+                    "address",
+                    "{type: object, required: [streetAddress], \
+                    properties: {streetAddress: {type: string, maxLength: 80}}}",
+                ),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(ts, generate(&model));
+    }
+}
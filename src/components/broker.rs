@@ -0,0 +1,198 @@
+use dioxus::prelude::*;
+use serde_json::Value;
+
+use crate::{
+    broker::{settings_path, Broker, BrokerSettings, Exchange},
+    components::container::Container,
+};
+
+const INPUT_STYLE: &str = "h-8 w-96 rounded-md border px-2 text-sm";
+const BUTTON_STYLE: &str = "px-3 rounded-md text-sm border hover:bg-gray-100";
+
+/// Exchanges of this session, the latest first.
+pub type ExchangeLog = Vec<Result<Exchange, String>>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Create,
+    Upsert,
+    Delete,
+}
+
+impl Action {
+    fn str(&self) -> &'static str {
+        match self {
+            Action::Create => "Create",
+            Action::Upsert => "Upsert",
+            Action::Delete => "Delete",
+        }
+    }
+
+    fn array() -> [Action; 3] {
+        [Action::Create, Action::Upsert, Action::Delete]
+    }
+}
+
+#[component]
+pub fn BrokerView() -> Element {
+    let mut settings = consume_context::<Signal<BrokerSettings>>();
+    let log = consume_context::<Signal<ExchangeLog>>();
+    let mut draft = use_signal(|| settings.read().clone());
+    let mut status = use_signal(|| None::<Result<String, String>>);
+
+    let save = move |_| {
+        let saved = draft();
+        status.set(Some(match saved.save() {
+            Ok(path) => Ok(format!("Saved to {}", path.display())),
+            Err(err) => Err(err.to_string()),
+        }));
+        settings.set(saved);
+    };
+
+    let fields = [
+        (
+            "Base URL",
+            "http://localhost:1026",
+            draft.read().base_url.clone(),
+        ),
+        (
+            "Tenant",
+            "NGSILD-Tenant header, optional",
+            draft.read().tenant.clone(),
+        ),
+        (
+            "Token",
+            "bearer token, optional",
+            draft.read().token.clone(),
+        ),
+    ];
+
+    rsx!(Container {
+        h1 {
+            class: "font-bold text-slate-950 text-lg",
+            "Broker"
+        },
+        p {
+            class: "text-xs text-slate-400",
+            "Saved in {settings_path().display()}, the CLI uses the same settings"
+        },
+        for (i, (label, placeholder, value)) in fields.into_iter().enumerate() {
+            div {
+                class: "flex flex-row gap-2 items-center",
+                span { class: "w-20 text-sm text-slate-500", "{label}" },
+                input {
+                    class: INPUT_STYLE,
+                    r#type: if i == 2 { "password" } else { "text" },
+                    value: "{value}",
+                    placeholder,
+                    spellcheck: false,
+                    oninput: move |event| {
+                        let mut draft = draft.write();
+                        match i {
+                            0 => draft.base_url = event.value(),
+                            1 => draft.tenant = event.value(),
+                            _ => draft.token = event.value(),
+                        }
+                    }
+                }
+            }
+        },
+        div {
+            class: "flex flex-row gap-2 items-center",
+            button {
+                class: BUTTON_STYLE,
+                disabled: draft() == settings(),
+                onclick: save,
+                "Save"
+            },
+            match status() {
+                Some(Ok(message)) => rsx!(span { class: "text-xs text-green-600", "{message}" }),
+                Some(Err(error)) => rsx!(span { class: "text-xs text-red-500", "{error}" }),
+                None => None,
+            }
+        },
+        h2 {
+            class: "text-sm font-medium text-slate-900",
+            "Exchanges"
+        },
+        if log.read().is_empty() {
+            p {
+                class: "text-xs text-slate-500",
+                "Entities sent from the entity builder show up here."
+            }
+        },
+        for exchange in log.read().iter().cloned() {
+            ExchangeView { exchange }
+        }
+    })
+}
+
+/// Buttons which send `entity` to the configured broker.
+#[component]
+pub fn BrokerActions(entity: Value, disabled: bool) -> Element {
+    let settings = consume_context::<Signal<BrokerSettings>>();
+    let mut log = consume_context::<Signal<ExchangeLog>>();
+    let mut last = use_signal(|| None::<Result<Exchange, String>>);
+    let id = entity["id"].as_str().unwrap_or_default().to_string();
+
+    if settings.read().base_url.is_empty() {
+        return rsx!(p {
+            class: "text-xs text-slate-500",
+            "Set up a broker in the Broker view to send this entity."
+        });
+    }
+
+    let send = move |action: Action| {
+        let broker = Broker::new(settings());
+        let entity = entity.clone();
+        let id = id.clone();
+        spawn(async move {
+            let exchange = match action {
+                Action::Create => broker.create(&entity).await,
+                Action::Upsert => broker.upsert(&entity).await,
+                Action::Delete => broker.delete(&id).await,
+            };
+            let exchange = exchange.map_err(|err| err.to_string());
+            log.write().insert(0, exchange.clone());
+            last.set(Some(exchange));
+        });
+    };
+
+    rsx!(div {
+        class: "flex flex-col gap-2",
+        div {
+            class: "flex flex-row gap-2 items-center",
+            span { class: "text-xs text-slate-500", "Broker" },
+            for action in Action::array() {
+                button {
+                    class: "px-2 rounded-md text-xs border hover:bg-gray-100",
+                    // Deleting only needs the id
+                    disabled: disabled && action != Action::Delete,
+                    onclick: {
+                        let send = send.clone();
+                        move |_| send(action)
+                    },
+                    "{action.str()}"
+                }
+            }
+        },
+        if let Some(exchange) = last() {
+            ExchangeView { exchange }
+        }
+    })
+}
+
+#[component]
+fn ExchangeView(exchange: Result<Exchange, String>) -> Element {
+    match exchange {
+        Ok(exchange) => rsx!(pre {
+            class: if exchange.is_success() {
+                "text-xs p-2 rounded-md bg-slate-50 text-slate-700 overflow-auto"
+            } else {
+                "text-xs p-2 rounded-md bg-red-50 text-red-700 overflow-auto"
+            },
+            "{exchange.to_text()}"
+        }),
+        Err(error) => rsx!(p { class: "text-xs text-red-500", "{error}" }),
+    }
+}
@@ -0,0 +1,340 @@
+use dioxus::prelude::*;
+
+use crate::{
+    cache::ModelCache,
+    codegen::{case::to_sentence_case, types::FieldType},
+    components::{
+        broker::BrokerActions,
+        highlight::{Highlighted, Syntax},
+    },
+    ngsi::{builder::EntityForm, Format},
+    smartdata::models::Property,
+};
+
+const INPUT_STYLE: &str = "h-6 w-full rounded-md border px-2 text-xs";
+const BUTTON_STYLE: &str = "px-2 rounded-md text-xs border hover:bg-gray-100";
+
+/// An entity made with the builder, the entity pickers of relationships offer it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeptEntity {
+    pub id: String,
+    pub typ: String,
+}
+
+/// A form for the checked properties of a model, which builds an entity while typing.
+#[component]
+pub fn EntityBuilder(selected_model: String) -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    let mut kept = consume_context::<Signal<Vec<KeptEntity>>>();
+    let mut form = use_signal(|| EntityForm::new(&selected_model));
+    let mut format = use_signal(|| Format::Normalized);
+    // Start over, when another model is selected
+    let mut form_model = use_signal(|| selected_model.clone());
+    if *form_model.peek() != selected_model {
+        form_model.set(selected_model.clone());
+        form.set(EntityForm::new(&selected_model));
+    }
+
+    let cache = cache.read();
+    let Some(model) = cache.get(&selected_model) else {
+        return rsx!("Loading...");
+    };
+    let (document, errors) = form.read().document(model, format());
+    // The broker speaks NGSI-LD and needs the kinds of the attributes, whatever is shown
+    let (sent, _) = form.read().document(model, Format::Normalized);
+    let json = serde_json::to_string_pretty(&document).unwrap_or_default();
+    let error_of = |name: &str| {
+        errors
+            .iter()
+            .find(|error| error.property == name)
+            .map(|error| error.message.clone())
+    };
+    let properties: Vec<_> = model
+        .properties
+        .iter()
+        .filter(|p| p.checked)
+        .map(|p| (p.clone(), error_of(&p.name)))
+        .collect();
+    let id_error = error_of("id");
+    let is_valid = errors.is_empty();
+
+    rsx!(div {
+        class: "flex flex-row gap-4",
+        div {
+            class: "w-96 flex flex-col gap-2",
+            FormField {
+                label: "Id",
+                required: true,
+                error: id_error,
+                input {
+                    class: INPUT_STYLE,
+                    value: "{form.read().id}",
+                    spellcheck: false,
+                    oninput: move |event| form.write().id = event.value()
+                }
+            },
+            for (property, error) in properties {
+                FormField {
+                    key: "{property.name}",
+                    label: label(&property),
+                    required: property.required,
+                    error,
+                    PropertyInput { property: property.clone(), form }
+                }
+            }
+        },
+        div {
+            class: "w-full flex flex-col gap-2",
+            div {
+                class: "flex flex-row gap-2 items-center",
+                select {
+                    class: "w-fit border text-sm text-slate-500",
+                    onchange: move |event| {
+                        if let Some(f) = Format::from_name(&event.value()) {
+                            format.set(f);
+                        }
+                    },
+                    for f in Format::array() {
+                        option {
+                            value: "{f.str()}",
+                            selected: format() == f,
+                            "{f.str()}"
+                        }
+                    }
+                },
+                button {
+                    class: BUTTON_STYLE,
+                    disabled: !is_valid,
+                    onclick: {
+                        let typ = selected_model.clone();
+                        move |_| {
+                            let id = form.read().id.clone();
+                            if !kept.read().iter().any(|entity| entity.id == id) {
+                                kept.write().push(KeptEntity { id, typ: typ.clone() });
+                            }
+                        }
+                    },
+                    "Keep for relationships"
+                },
+                button {
+                    class: BUTTON_STYLE,
+                    onclick: {
+                        let name = selected_model.clone();
+                        move |_| form.set(EntityForm::new(&name))
+                    },
+                    "Reset"
+                }
+            },
+            if is_valid {
+                p { class: "text-xs text-green-600", "The entity is valid." }
+            } else {
+                p {
+                    class: "text-xs text-red-500",
+                    "{errors.len()} problem(s), see the marked fields."
+                }
+            },
+            if format() != Format::Normalized {
+                p {
+                    class: "text-xs text-slate-500",
+                    "The broker is sent the entity in NGSI-LD normalized form."
+                }
+            },
+            BrokerActions { entity: sent, disabled: !is_valid },
+            Highlighted { code: json, syntax: Syntax::Json }
+        }
+    })
+}
+
+/// The property name as a label, with the units of the values.
+fn label(property: &Property) -> String {
+    let label = to_sentence_case(&property.name);
+    match property.units() {
+        Some(units) => format!("{label} ({units})"),
+        None => label,
+    }
+}
+
+#[component]
+fn FormField(label: String, required: bool, error: Option<String>, children: Element) -> Element {
+    rsx!(div {
+        class: "flex flex-col gap-1",
+        label {
+            class: "text-xs text-slate-500",
+            "{label}",
+            if required {
+                span { class: "text-red-400", " *" }
+            }
+        },
+        {children},
+        if let Some(error) = error {
+            p { class: "text-xs text-red-500", "{error}" }
+        }
+    })
+}
+
+#[component]
+fn PropertyInput(property: Property, form: Signal<EntityForm>) -> Element {
+    let mut form = form;
+    let name = property.name.clone();
+    let value = form.read().inputs.get(&name).cloned().unwrap_or_default();
+    let set = move |value: String| {
+        form.write().inputs.insert(name.clone(), value);
+    };
+
+    let input_type = |typ: &'static str| {
+        let mut set = set.clone();
+        rsx!(input {
+            class: INPUT_STYLE,
+            r#type: typ,
+            value: "{value}",
+            step: "any",
+            spellcheck: false,
+            oninput: move |event| set(event.value())
+        })
+    };
+
+    match FieldType::from_property(&property) {
+        FieldType::Integer | FieldType::Number => input_type("number"),
+        FieldType::DateTime => input_type("datetime-local"),
+        FieldType::Date => input_type("date"),
+        FieldType::Time => input_type("time"),
+        FieldType::Boolean => rsx!(Choice {
+            value,
+            choices: vec!["true".to_string(), "false".to_string()],
+            onchange: set
+        }),
+        FieldType::Enum(values) => rsx!(Choice {
+            value,
+            choices: values,
+            onchange: set
+        }),
+        FieldType::Relationship(target) => rsx!(EntityPicker {
+            value,
+            target,
+            onchange: set
+        }),
+        FieldType::Geo(variant) => rsx!(CoordinateEditor {
+            name: property.name.clone(),
+            variant: variant.unwrap_or_else(|| "Point".to_string()),
+            form
+        }),
+        FieldType::Object | FieldType::Array(_) => {
+            let mut set = set.clone();
+            rsx!(textarea {
+                class: "h-16 w-full rounded-md border px-2 text-xs font-mono",
+                value: "{value}",
+                placeholder: "json",
+                spellcheck: false,
+                oninput: move |event| set(event.value())
+            })
+        }
+        FieldType::String | FieldType::Uri => input_type("text"),
+    }
+}
+
+/// A dropdown with an empty choice, which leaves the value out.
+#[component]
+fn Choice(value: String, choices: Vec<String>, onchange: EventHandler<String>) -> Element {
+    rsx!(select {
+        class: "w-full border text-xs text-slate-500",
+        onchange: move |event| onchange.call(event.value()),
+        option { value: "", selected: value.is_empty(), "" },
+        for choice in choices {
+            option {
+                value: "{choice}",
+                selected: value == choice,
+                "{choice}"
+            }
+        }
+    })
+}
+
+/// An id input, which also offers the kept entities of the target type.
+#[component]
+fn EntityPicker(value: String, target: String, onchange: EventHandler<String>) -> Element {
+    let kept = consume_context::<Signal<Vec<KeptEntity>>>();
+    let candidates: Vec<_> = kept
+        .read()
+        .iter()
+        .filter(|entity| entity.typ.eq_ignore_ascii_case(&target))
+        .map(|entity| entity.id.clone())
+        .collect();
+
+    let oninput = onchange.clone();
+
+    rsx!(div {
+        class: "flex flex-row gap-1",
+        input {
+            class: INPUT_STYLE,
+            value: "{value}",
+            placeholder: "urn:ngsi-ld:{target}:...",
+            spellcheck: false,
+            oninput: move |event| oninput.call(event.value())
+        },
+        if !candidates.is_empty() {
+            select {
+                class: "w-40 border text-xs text-slate-500",
+                onchange: move |event| onchange.call(event.value()),
+                option { value: "{value}", "Pick a {target}" },
+                for id in candidates {
+                    option { value: "{id}", "{id}" }
+                }
+            }
+        }
+    })
+}
+
+/// Longitude and latitude rows of a geometry, see `EntityForm::positions`.
+#[component]
+fn CoordinateEditor(name: String, variant: String, form: Signal<EntityForm>) -> Element {
+    let mut form = form;
+    let rows = form
+        .read()
+        .positions
+        .get(&name)
+        .cloned()
+        .unwrap_or_default();
+    let can_add = variant != "Point" || rows.is_empty();
+
+    rsx!(div {
+        class: "flex flex-col gap-1",
+        span { class: "text-xs text-slate-400", "{variant}, longitude and latitude" },
+        for (i, [longitude, latitude]) in rows.into_iter().enumerate() {
+            div {
+                class: "flex flex-row gap-1",
+                for (axis, coordinate) in [longitude, latitude].into_iter().enumerate() {
+                    input {
+                        class: INPUT_STYLE,
+                        r#type: "number",
+                        step: "any",
+                        value: "{coordinate}",
+                        oninput: {
+                            let name = name.clone();
+                            move |event: FormEvent| {
+                                form.write().set_coordinate(&name, i, axis, event.value())
+                            }
+                        }
+                    }
+                }
+                button {
+                    class: BUTTON_STYLE,
+                    onclick: {
+                        let name = name.clone();
+                        move |_| form.write().remove_position(&name, i)
+                    },
+                    "✕"
+                }
+            }
+        },
+        if can_add {
+            button {
+                class: "w-fit {BUTTON_STYLE}",
+                onclick: {
+                    let name = name.clone();
+                    move |_| form.write().add_position(&name)
+                },
+                "+ position"
+            }
+        }
+    })
+}
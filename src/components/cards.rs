@@ -1,9 +1,6 @@
 use dioxus::prelude::*;
 
-use crate::{
-    smartdata::models::{data_model_yaml, DataModelRepo},
-    ModelData,
-};
+use crate::{smartdata::models::DataModelRepo, ModelData, Pins};
 
 #[component]
 pub fn RepoCard(
@@ -13,14 +10,19 @@ pub fn RepoCard(
     collapsed: bool,
 ) -> Element {
     let mut collapsed = use_signal(|| if !filter.is_empty() { false } else { collapsed });
+    let pins = consume_context::<Signal<Pins>>();
 
     let item_len = data_model_repo.data_models.len();
     let collapse_icon = if collapsed() { "▲" } else { "▼" };
 
     let mut update_model_data = {
         move |repo: String, name: String| {
-            let url = data_model_yaml(&repo, &name);
-            model_data.set(Some(ModelData { repo, name, url }));
+            let git_ref = pins.read().model.clone();
+            model_data.set(Some(ModelData {
+                repo,
+                name,
+                git_ref,
+            }));
         }
     };
 
@@ -1,12 +1,114 @@
+use std::collections::HashMap;
+
 use dioxus::prelude::*;
 
-use crate::components::container::Container;
+use crate::{
+    cache::ModelCache,
+    codegen::{
+        jsonschema::{self, Documents},
+        templates::{templates_dir, Template},
+        Language,
+    },
+    components::hooks::use_synced,
+};
+
+/// Prefix of the select values of user templates, which keeps them apart from the languages.
+const TEMPLATE_PREFIX: &str = "template:";
 
 #[component]
-pub fn Codeview() -> Element {
-    rsx! {
-        Container {
+pub fn Codeview(selected_model: String) -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    let mut language = use_signal(|| Language::JavaScript);
+    let mut template = use_signal(|| None::<String>);
+    // The last output by model and language, so schemas keep their field order and numbers
+    // while properties are checked
+    let mut generated = use_signal(HashMap::<(String, &str), String>::new);
+    let model_name = use_synced(selected_model.clone());
+    // The remote documents the `$ref`s of the model point to, JSON Schemas inline them
+    let documents = use_resource(move || async move {
+        let model = cache.peek().get(&model_name()).cloned();
+        match model {
+            Some(model) if language() == Language::JsonSchema => {
+                jsonschema::fetch_documents(&model).await
+            }
+            _ => Documents::new(),
+        }
+    });
 
+    // Read on every render, so new files in the templates directory show up
+    let templates = Template::list_saved();
+    let templates_dir = templates_dir();
+
+    let code = match cache.read().get(&selected_model) {
+        Some(model) => match templates
+            .iter()
+            .find(|t| Some(&t.name) == template().as_ref())
+        {
+            Some(template) => template.render(model).map_err(|err| err.to_string()),
+            None => match &*documents.read() {
+                Some(documents) => {
+                    let key = (selected_model.clone(), language().str());
+                    let previous = generated.peek().get(&key).cloned();
+                    let code = language().regenerate(model, previous.as_deref(), documents);
+                    if let Ok(code) = &code {
+                        if previous.as_ref() != Some(code) {
+                            generated.write().insert(key, code.clone());
+                        }
+                    }
+                    code.map_err(|err| err.to_string())
+                }
+                None => Ok(String::from("Loading the documents the $refs point to...")),
+            },
+        },
+        None => return rsx!("Loading..."),
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-2",
+            div {
+                class: "flex flex-row gap-2 items-center",
+                select {
+                    class: "w-fit border text-sm text-slate-500",
+                    onchange: move |event| {
+                        let value = event.value();
+                        match value.strip_prefix(TEMPLATE_PREFIX) {
+                            Some(name) => template.set(Some(name.to_string())),
+                            None => {
+                                template.set(None);
+                                if let Some(l) = Language::from_name(&value) {
+                                    language.set(l);
+                                }
+                            }
+                        }
+                    },
+                    for l in Language::array() {
+                        option {
+                            value: "{l.str()}",
+                            selected: template().is_none() && language() == l,
+                            "{l.str()}"
+                        }
+                    },
+                    for t in templates.iter() {
+                        option {
+                            value: "{TEMPLATE_PREFIX}{t.name}",
+                            selected: template().as_ref() == Some(&t.name),
+                            "{t.name} (template)"
+                        }
+                    }
+                },
+                span {
+                    class: "text-xs text-slate-400",
+                    "Templates are read from {templates_dir.display()}"
+                }
+            },
+            match code {
+                Ok(code) => rsx!(pre {
+                    class: "text-xs p-2 rounded-md bg-slate-50 text-slate-700 overflow-auto",
+                    "{code}"
+                }),
+                Err(error) => rsx!(p { class: "text-xs text-red-500", "{error}" }),
+            }
         }
     }
 }
@@ -0,0 +1,143 @@
+use dioxus::prelude::*;
+
+use crate::{
+    cache::ModelCache,
+    components::{container::Container, nav::View},
+    smartdata::compare::{compare, merge, Status},
+};
+
+#[component]
+pub fn Compare(view: Signal<View>) -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    let mut compared = consume_context::<Signal<Vec<String>>>();
+    let mut merged_name = use_signal(|| String::from(""));
+    let mut error = use_signal(|| None::<String>);
+
+    let models: Vec<_> = compared
+        .read()
+        .iter()
+        .filter_map(|name| cache.read().get(name).cloned())
+        .collect();
+
+    if models.len() < 2 {
+        return rsx!(Container {
+            h1 {
+                class: "font-bold text-slate-950 text-lg",
+                "Compare"
+            },
+            p {
+                class: "text-sm text-slate-500",
+                "Add at least two models to the comparison with the compare button of a model."
+            }
+        });
+    }
+
+    let compared_properties = compare(&models);
+    let merge_models = {
+        let models = models.clone();
+        move |_: MouseEvent| {
+            let name = merged_name();
+            if name.is_empty() {
+                return;
+            }
+
+            match merge(&models, &name).save_new() {
+                Ok(_) => view.set(View::Profiles),
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        }
+    };
+
+    rsx!(Container {
+        h1 {
+            class: "font-bold text-slate-950 text-lg",
+            "Compare"
+        },
+        div {
+            class: "overflow-auto",
+            table {
+                class: "text-sm",
+                thead {
+                    tr {
+                        th { class: "p-1 text-left", "Property" },
+                        for model in models.iter() {
+                            th {
+                                class: "p-1 text-left",
+                                "{model.name} ",
+                                button {
+                                    class: "text-xs text-slate-400 hover:text-slate-900",
+                                    onclick: {
+                                        let name = model.name.clone();
+                                        move |_| compared.write().retain(|n| *n != name)
+                                    },
+                                    "✕"
+                                }
+                            }
+                        },
+                        th { class: "p-1 text-left", "Status" },
+                    }
+                },
+                tbody {
+                    for compared_property in compared_properties {
+                        tr {
+                            class: match compared_property.status {
+                                Status::Shared => "",
+                                Status::Unique => "bg-sky-50",
+                                Status::Partial => "bg-slate-50",
+                                Status::Different(_) => "bg-amber-100",
+                            },
+                            td {
+                                class: "p-1 font-medium text-slate-900",
+                                "{compared_property.name}"
+                            },
+                            for definition in compared_property.definitions.iter() {
+                                td {
+                                    class: "p-1 text-xs text-slate-500",
+                                    match definition {
+                                        Some(property) => {
+                                            let typ = property
+                                                .typ
+                                                .clone()
+                                                .or_else(|| property.ngsi_type().map(str::to_string))
+                                                .unwrap_or_default();
+                                            let checked = if property.checked { " ✓" } else { "" };
+                                            rsx!("{typ}{checked}")
+                                        }
+                                        None => rsx!("—"),
+                                    }
+                                }
+                            },
+                            td {
+                                class: "p-1 text-xs text-slate-500",
+                                match &compared_property.status {
+                                    Status::Different(differences) => {
+                                        rsx!("different: {differences.join(\", \")}")
+                                    }
+                                    status => rsx!("{status.str()}"),
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        div {
+            class: "flex flex-row gap-2",
+            input {
+                class: "h-8 rounded-md border px-2 text-sm",
+                value: "{merged_name}",
+                placeholder: "Name of the new profile",
+                spellcheck: false,
+                oninput: move |event| merged_name.set(event.value())
+            },
+            button {
+                class: "px-3 rounded-md text-sm border hover:bg-gray-100",
+                onclick: merge_models,
+                "Merge selection into new profile"
+            }
+        },
+        if let Some(error) = error() {
+            p { class: "text-sm text-red-500", "{error}" }
+        }
+    })
+}
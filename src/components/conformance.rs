@@ -0,0 +1,201 @@
+use dioxus::prelude::*;
+
+use crate::{
+    broker::{Broker, BrokerSettings},
+    cache::{fetch_missing, ModelCache},
+    components::{
+        container::Container,
+        highlight::{Highlighted, Syntax},
+        nav::View,
+    },
+    ngsi::conformance::{short_name, ConformanceReport, TypeReport},
+    smartdata::models::ModelList,
+    ModelData, Pins,
+};
+
+const BUTTON_STYLE: &str = "px-3 rounded-md text-sm border hover:bg-gray-100";
+
+/// Checks the entities of the configured broker against the models of their types.
+#[component]
+pub fn Conformance(
+    list: ModelList,
+    model_data: Signal<Option<ModelData>>,
+    view: Signal<View>,
+) -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    let settings = consume_context::<Signal<BrokerSettings>>();
+    let pins = consume_context::<Signal<Pins>>();
+    let mut report = use_signal(|| None::<ConformanceReport>);
+    let mut status = use_signal(|| None::<String>);
+    let mut show_json = use_signal(|| false);
+
+    let model_data_of = move |list: &ModelList, typ: &str| {
+        let name = short_name(typ);
+        list.repo_of(name).map(|repo| ModelData {
+            repo: repo.name.clone(),
+            name: name.to_string(),
+            git_ref: pins.read().model.clone(),
+        })
+    };
+
+    let check = {
+        let list = list.clone();
+        move |_| {
+            let broker = Broker::new(settings());
+            let list = list.clone();
+            status.set(Some(String::from("Reading the broker...")));
+            spawn(async move {
+                let deployment = match broker.entities_by_type().await {
+                    Ok(deployment) => deployment,
+                    Err(err) => {
+                        status.set(Some(err.to_string()));
+                        return;
+                    }
+                };
+                status.set(Some(String::from("Loading the models...")));
+                let models = deployment
+                    .iter()
+                    .filter_map(|(typ, _)| model_data_of(&list, typ))
+                    .collect();
+                let errors = fetch_missing(cache, models).await;
+
+                let types = deployment
+                    .iter()
+                    .map(|(typ, entities)| {
+                        let model = model_data_of(&list, typ)
+                            .and_then(|_| cache.read().get(short_name(typ)).cloned());
+                        TypeReport::new(typ, model.as_ref(), entities)
+                    })
+                    .collect();
+                let git_ref = pins.read().model.clone();
+                report.set(Some(ConformanceReport::new(
+                    broker.base_url(),
+                    &git_ref,
+                    types,
+                )));
+                status.set((!errors.is_empty()).then(|| errors.join(", ")));
+            });
+        }
+    };
+
+    let rendered_report = report().map(|report| {
+        let json = report.to_json();
+        let entities: usize = report.types.iter().map(|t| t.entities.len()).sum();
+        let conforming: usize = report.types.iter().map(TypeReport::conforming).sum();
+        let unchecked = match report.unchecked.len() {
+            0 => String::new(),
+            1 => String::from(" 1 type was not checked."),
+            n => format!(" {n} types were not checked."),
+        };
+
+        rsx!(
+            div {
+                class: "flex flex-row gap-2 items-center",
+                p {
+                    class: if report.conforming { "text-sm text-green-600" } else { "text-sm text-red-500" },
+                    "{conforming} of {entities} entities in {report.types.len()} types conform.{unchecked}"
+                },
+                button {
+                    class: BUTTON_STYLE,
+                    onclick: move |_| show_json.set(!show_json()),
+                    if show_json() { "Hide JSON" } else { "Show JSON" }
+                }
+            },
+            if show_json() {
+                Highlighted { code: json, syntax: Syntax::Json }
+            },
+            for type_report in report.types {
+                TypeSection {
+                    target: model_data_of(&list, &type_report.typ),
+                    type_report: type_report.clone(),
+                    model_data,
+                    view
+                }
+            }
+        )
+    });
+
+    rsx!(Container {
+        h1 {
+            class: "font-bold text-slate-950 text-lg",
+            "Conformance"
+        },
+        p {
+            class: "text-xs text-slate-500",
+            "Reads every entity of the broker set up in the Broker view and checks it against the model of its type."
+        },
+        div {
+            class: "flex flex-row gap-2 items-center",
+            button {
+                class: BUTTON_STYLE,
+                disabled: settings.read().base_url.is_empty(),
+                onclick: check,
+                "Check the broker"
+            },
+            if let Some(status) = status() {
+                span { class: "text-xs text-slate-500", "{status}" }
+            }
+        },
+        {rendered_report}
+    })
+}
+
+/// The entities of a type, the ones which do not conform with their issues.
+#[component]
+fn TypeSection(
+    type_report: TypeReport,
+    target: Option<ModelData>,
+    model_data: Signal<Option<ModelData>>,
+    view: Signal<View>,
+) -> Element {
+    let name = short_name(&type_report.typ).to_string();
+    let total = type_report.entities.len();
+    let conforming = type_report.conforming();
+    let issues: Vec<_> = type_report
+        .entities
+        .iter()
+        .filter(|entity| !entity.issues.is_empty())
+        .map(|entity| {
+            let texts: Vec<_> = entity.issues.iter().map(|issue| issue.to_text()).collect();
+            (entity.id.clone(), texts)
+        })
+        .collect();
+
+    rsx!(div {
+        class: "flex flex-col gap-1 border-t pt-2",
+        div {
+            class: "flex flex-row gap-2 items-center",
+            h2 { class: "text-sm font-medium text-slate-900", "{name}" },
+            match (&type_report.repo, target) {
+                (Some(repo), Some(target)) => rsx!(
+                    button {
+                        class: "text-xs text-sky-700 hover:underline",
+                        onclick: move |_| {
+                            model_data.set(Some(target.clone()));
+                            view.set(View::Model);
+                        },
+                        "dataModel.{repo}"
+                    },
+                    span { class: "text-xs text-slate-500", "{conforming} of {total} conform" }
+                ),
+                (None, Some(_)) => rsx!(span {
+                    class: "text-xs text-red-500",
+                    "{total} entities, the model failed to load, not checked"
+                }),
+                _ => rsx!(span {
+                    class: "text-xs text-red-500",
+                    "{total} entities, no smart data model, not checked"
+                }),
+            }
+        },
+        for (id, texts) in issues {
+            div {
+                class: "flex flex-col pl-2",
+                span { class: "text-xs font-mono text-slate-700", "{id}" },
+                for text in texts {
+                    span { class: "text-xs text-red-500 pl-2", "{text}" }
+                }
+            }
+        }
+    })
+}
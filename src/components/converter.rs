@@ -0,0 +1,82 @@
+use dioxus::prelude::*;
+use serde_json::Value;
+
+use crate::{
+    components::highlight::{Highlighted, Syntax},
+    ngsi::{
+        convert::{convert, detect},
+        Format,
+    },
+    smartdata::models::ParsedModel,
+};
+
+/// Converts a pasted entity of `model` into another representation.
+#[component]
+pub fn Converter(model: ParsedModel) -> Element {
+    let mut input = use_signal(String::new);
+    let mut to = use_signal(|| Format::KeyValues);
+
+    let converted = match input.read().trim() {
+        "" => None,
+        text => Some(
+            serde_json::from_str::<Value>(text)
+                .map_err(|err| format!("No valid json: {err}"))
+                .and_then(|entity| {
+                    let from = detect(&entity);
+                    let (converted, notes) = convert(Some(&model), &entity, from, to())?;
+                    let json = serde_json::to_string_pretty(&converted).unwrap_or_default();
+                    Ok((from, json, notes))
+                }),
+        ),
+    };
+
+    rsx!(div {
+        class: "flex flex-row gap-4",
+        div {
+            class: "w-1/2 flex flex-col gap-2",
+            span {
+                class: "text-xs text-slate-500",
+                "Paste an entity of {model.name} in any representation"
+            },
+            textarea {
+                class: "h-96 w-full rounded-md border p-2 text-xs font-mono",
+                value: "{input}",
+                spellcheck: false,
+                oninput: move |event| input.set(event.value())
+            }
+        },
+        div {
+            class: "w-1/2 flex flex-col gap-2",
+            div {
+                class: "flex flex-row gap-2 items-center",
+                span { class: "text-xs text-slate-500", "Convert to" },
+                select {
+                    class: "w-fit border text-sm text-slate-500",
+                    onchange: move |event| {
+                        if let Some(f) = Format::from_name(&event.value()) {
+                            to.set(f);
+                        }
+                    },
+                    for f in Format::array() {
+                        option {
+                            value: "{f.str()}",
+                            selected: to() == f,
+                            "{f.str()}"
+                        }
+                    }
+                }
+            },
+            match converted {
+                Some(Ok((from, json, notes))) => rsx!(
+                    span { class: "text-xs text-slate-400", "Read as {from.str()}" },
+                    for note in notes {
+                        p { class: "text-xs text-amber-600", "{note}" }
+                    },
+                    Highlighted { code: json, syntax: Syntax::Json }
+                ),
+                Some(Err(error)) => rsx!(p { class: "text-xs text-red-500", "{error}" }),
+                None => None,
+            }
+        }
+    })
+}
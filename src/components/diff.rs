@@ -0,0 +1,121 @@
+use dioxus::prelude::*;
+
+use crate::{
+    components::{
+        container::Container,
+        highlight::{Highlighted, Syntax},
+    },
+    smartdata::diff::{ModelDiff, Severity},
+    ModelData, ShowError,
+};
+
+const INPUT_STYLE: &str = "h-8 w-40 rounded-md border px-2 text-sm";
+
+#[component]
+pub fn Diff(model_data: Signal<Option<ModelData>>) -> Element {
+    let (repo, name) = match model_data.read().as_ref() {
+        Some(model_data) => (model_data.repo.clone(), model_data.name.clone()),
+        None => (String::new(), String::new()),
+    };
+
+    let mut old_ref = use_signal(|| String::from(""));
+    let mut new_ref = use_signal(|| String::from("master"));
+    let mut request = use_signal(|| None);
+    let mut show_json = use_signal(|| false);
+
+    let diff = use_resource(move || async move {
+        let (repo, name, old_ref, new_ref): (String, String, String, String) = request()?;
+        Some(ModelDiff::fetch(&repo, &name, &old_ref, &new_ref).await)
+    });
+
+    let rendered_diff = match &*diff.read() {
+        Some(Some(Ok(diff))) => rsx!(
+            p {
+                class: if diff.breaking { "text-sm text-red-500" } else { "text-sm text-green-600" },
+                if diff.breaking {
+                    "Breaking changes between {diff.old_ref} ({diff.old_version}) and {diff.new_ref} ({diff.new_version})."
+                } else {
+                    "Compatible changes between {diff.old_ref} ({diff.old_version}) and {diff.new_ref} ({diff.new_version})."
+                }
+            },
+            table {
+                class: "text-sm",
+                for change in diff.changes.iter() {
+                    tr {
+                        td {
+                            class: "pr-4 py-1 font-medium text-slate-900",
+                            "{change.property}"
+                        },
+                        td {
+                            class: match change.severity {
+                                Severity::Breaking => "pr-4 py-1 text-xs text-red-500",
+                                Severity::Compatible => "pr-4 py-1 text-xs text-green-600",
+                            },
+                            "{change.kind.str()}"
+                        },
+                        td {
+                            class: "py-1 text-xs text-slate-500",
+                            "{change.detail}"
+                        }
+                    }
+                }
+            },
+            button {
+                class: "w-fit text-xs text-slate-500 hover:text-slate-900",
+                onclick: move |_| show_json.set(!show_json()),
+                if show_json() { "Hide JSON report" } else { "Show JSON report" }
+            },
+            if show_json() {
+                Highlighted { code: diff.to_json(), syntax: Syntax::Json }
+            }
+        ),
+        Some(Some(Err(err))) => rsx!(ShowError {
+            error: err.to_string()
+        }),
+        Some(None) => None,
+        None => rsx!(p { "Loading..." }),
+    };
+
+    rsx!(Container {
+        h1 {
+            class: "font-bold text-slate-950 text-lg",
+            "Diff {name}"
+        },
+        if name.is_empty() {
+            p {
+                class: "text-sm text-slate-500",
+                "Select a model to diff it between two git refs."
+            }
+        } else {
+            div {
+                class: "flex flex-row gap-2",
+                input {
+                    class: INPUT_STYLE,
+                    value: "{old_ref}",
+                    placeholder: "Old ref, e.g. a tag",
+                    spellcheck: false,
+                    oninput: move |event| old_ref.set(event.value())
+                },
+                input {
+                    class: INPUT_STYLE,
+                    value: "{new_ref}",
+                    placeholder: "New ref",
+                    spellcheck: false,
+                    oninput: move |event| new_ref.set(event.value())
+                },
+                button {
+                    class: "px-3 rounded-md text-sm border hover:bg-gray-100",
+                    onclick: move |_| {
+                        request.set(Some((repo.clone(), name.clone(), old_ref(), new_ref())))
+                    },
+                    "Diff"
+                }
+            }
+            p {
+                class: "text-xs text-slate-400",
+                "In CI: sdm_browser diff <repo> <model> <old-ref> <new-ref>"
+            }
+            {rendered_diff}
+        }
+    })
+}
@@ -0,0 +1,191 @@
+use std::f64::consts::PI;
+
+use dioxus::prelude::*;
+
+use crate::{
+    cache::{fetch_missing, ModelCache},
+    components::{container::Container, nav::View},
+    smartdata::{graph::Graph, models::ModelList},
+    ModelData, Pins,
+};
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 600.0;
+const NODE_RADIUS: f64 = 6.0;
+const BUTTON_STYLE: &str = "px-3 rounded-md text-sm border hover:bg-gray-100";
+
+#[component]
+pub fn RelationshipGraph(
+    list: ModelList,
+    model_data: Signal<Option<ModelData>>,
+    view: Signal<View>,
+) -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    let pins = consume_context::<Signal<Pins>>();
+    let mut status = use_signal(|| None::<String>);
+
+    let models: Vec<_> = cache.read().models().into_iter().cloned().collect();
+    let graph = Graph::new(&models, &list.model_names());
+
+    // Nodes are placed on a circle, which keeps every edge visible without a physics layout
+    let radius = HEIGHT / 2.0 - 60.0;
+    let position = |name: &str| {
+        let i = graph.nodes.iter().position(|n| n == name).unwrap_or(0);
+        let angle = 2.0 * PI * i as f64 / graph.nodes.len().max(1) as f64;
+        (
+            WIDTH / 2.0 + radius * angle.cos(),
+            HEIGHT / 2.0 + radius * angle.sin(),
+        )
+    };
+
+    let model_data_of = {
+        let list = list.clone();
+        move |name: &str| {
+            list.repo_of(name).map(|repo| ModelData {
+                repo: repo.name.clone(),
+                name: name.to_string(),
+                git_ref: pins.read().model.clone(),
+            })
+        }
+    };
+
+    let mut load = {
+        let model_data_of = model_data_of.clone();
+        move |names: Vec<String>| {
+            let models = names.iter().filter_map(|n| model_data_of(n)).collect();
+            status.set(Some(String::from("Loading...")));
+            spawn(async move {
+                let errors = fetch_missing(cache, models).await;
+                status.set((!errors.is_empty()).then(|| errors.join(", ")));
+            });
+        }
+    };
+
+    let repo_models = match model_data.read().as_ref() {
+        Some(selected) => list
+            .entries
+            .iter()
+            .find(|dmr| dmr.name == selected.repo)
+            .map(|dmr| dmr.data_models.clone())
+            .unwrap_or_default(),
+        None => vec![],
+    };
+    let linked_models: Vec<_> = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.resolved)
+        .map(|edge| edge.to.clone())
+        .collect();
+
+    let rendered_edges = graph.edges.iter().map(|edge| {
+        let (x1, y1) = position(&edge.from);
+        let (x2, y2) = position(&edge.to);
+        // Stop the line at the border of the target node, so the arrow stays visible
+        let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(1.0);
+        let x2 = x2 - (x2 - x1) / length * NODE_RADIUS;
+        let y2 = y2 - (y2 - y1) / length * NODE_RADIUS;
+        let dash = if edge.resolved { "" } else { "4 2" };
+
+        rsx!(g {
+            line {
+                x1: "{x1}",
+                y1: "{y1}",
+                x2: "{x2}",
+                y2: "{y2}",
+                stroke: "#94a3b8",
+                stroke_dasharray: dash,
+                marker_end: "url(#arrow)",
+            },
+            text {
+                x: "{(x1 + x2) / 2.0}",
+                y: "{(y1 + y2) / 2.0}",
+                font_size: 9,
+                fill: "#94a3b8",
+                text_anchor: "middle",
+                "{edge.property}"
+            }
+        })
+    });
+
+    let rendered_nodes = graph.nodes.iter().map(|name| {
+        let (x, y) = position(name);
+        let loaded = models.iter().any(|m| &m.name == name);
+        let target = model_data_of(name);
+        let is_clickable = target.is_some();
+
+        rsx!(g {
+            class: if is_clickable { "hover:cursor-pointer" } else { "" },
+            onclick: move |_| {
+                if let Some(target) = target.clone() {
+                    model_data.set(Some(target));
+                    view.set(View::Model);
+                }
+            },
+            circle {
+                cx: "{x}",
+                cy: "{y}",
+                r: NODE_RADIUS,
+                fill: if loaded { "#0f172a" } else if is_clickable { "#94a3b8" } else { "#e2e8f0" },
+            },
+            text {
+                x: "{x}",
+                y: "{y - 10.0}",
+                font_size: 11,
+                fill: "#0f172a",
+                text_anchor: "middle",
+                "{name}"
+            }
+        })
+    });
+
+    rsx!(Container {
+        h1 {
+            class: "font-bold text-slate-950 text-lg",
+            "Relationships"
+        },
+        p {
+            class: "text-xs text-slate-500",
+            "Relationships between all loaded models. Dark nodes are loaded, click a node to open it."
+        },
+        div {
+            class: "flex flex-row gap-2",
+            button {
+                class: BUTTON_STYLE,
+                disabled: repo_models.is_empty(),
+                onclick: {
+                    let mut load = load.clone();
+                    move |_| load(repo_models.clone())
+                },
+                "Load models of the selected repo"
+            },
+            button {
+                class: BUTTON_STYLE,
+                onclick: move |_| load(linked_models.clone()),
+                "Load linked models"
+            },
+        },
+        if let Some(status) = status() {
+            p { class: "text-xs text-slate-500", "{status}" }
+        },
+        svg {
+            class: "border rounded-lg",
+            width: WIDTH,
+            height: HEIGHT,
+            view_box: "0 0 {WIDTH} {HEIGHT}",
+            defs {
+                marker {
+                    id: "arrow",
+                    view_box: "0 0 10 10",
+                    ref_x: 10,
+                    ref_y: 5,
+                    marker_width: 6,
+                    marker_height: 6,
+                    orient: "auto-start-reverse",
+                    path { d: "M 0 0 L 10 5 L 0 10 z", fill: "#94a3b8" }
+                }
+            },
+            {rendered_edges},
+            {rendered_nodes}
+        }
+    })
+}
@@ -0,0 +1,203 @@
+use dioxus::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Syntax {
+    Yaml,
+    Json,
+}
+
+impl Syntax {
+    /// Guesses the syntax from a file name, everything that is not json is shown as yaml.
+    pub fn from_file(file: &str) -> Self {
+        if file.ends_with(".json") || file.ends_with(".jsonld") {
+            Syntax::Json
+        } else {
+            Syntax::Yaml
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token {
+    Key,
+    String,
+    Number,
+    Literal,
+    Comment,
+    Plain,
+}
+
+impl Token {
+    fn class(&self) -> &'static str {
+        match self {
+            Token::Key => "text-sky-700",
+            Token::String => "text-emerald-700",
+            Token::Number => "text-amber-600",
+            Token::Literal => "text-violet-600",
+            Token::Comment => "text-slate-400 italic",
+            Token::Plain => "text-slate-600",
+        }
+    }
+}
+
+#[component]
+pub fn Highlighted(code: String, syntax: Syntax) -> Element {
+    let tokens = tokenize(&code, syntax);
+
+    rsx!(pre {
+        class: "text-xs p-2 rounded-md bg-slate-50 overflow-auto",
+        for (token, text) in tokens {
+            span {
+                class: token.class(),
+                "{text}"
+            }
+        }
+    })
+}
+
+pub fn tokenize(code: &str, syntax: Syntax) -> Vec<(Token, String)> {
+    let mut tokens = vec![];
+    for line in code.lines() {
+        match syntax {
+            Syntax::Yaml => tokenize_yaml_line(line, &mut tokens),
+            Syntax::Json => tokenize_json_line(line, &mut tokens),
+        }
+        push(&mut tokens, Token::Plain, "\n");
+    }
+    tokens
+}
+
+fn tokenize_yaml_line(line: &str, tokens: &mut Vec<(Token, String)>) {
+    let trimmed = line.trim_start();
+    let (indent, rest) = line.split_at(line.len() - trimmed.len());
+    push(tokens, Token::Plain, indent);
+
+    if trimmed.starts_with('#') {
+        push(tokens, Token::Comment, trimmed);
+        return;
+    }
+
+    let rest = match rest.strip_prefix("- ") {
+        Some(item) => {
+            push(tokens, Token::Plain, "- ");
+            item
+        }
+        None => rest,
+    };
+
+    // A key is everything up to the first `: ` (or a trailing `:`) outside of quotes
+    let key_end = if rest.starts_with('"') || rest.starts_with('\'') {
+        None
+    } else {
+        rest.find(": ")
+            .or_else(|| rest.ends_with(':').then(|| rest.len() - 1))
+    };
+
+    let value = match key_end {
+        Some(end) => {
+            push(tokens, Token::Key, &rest[..end]);
+            push(tokens, Token::Plain, ":");
+            &rest[end + 1..]
+        }
+        None => rest,
+    };
+
+    let trimmed_value = value.trim_start();
+    push(
+        tokens,
+        Token::Plain,
+        &value[..value.len() - trimmed_value.len()],
+    );
+    push(tokens, classify_scalar(trimmed_value), trimmed_value);
+}
+
+fn tokenize_json_line(line: &str, tokens: &mut Vec<(Token, String)>) {
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == '"' {
+            let mut end = line.len();
+            let mut escaped = false;
+            for (i, c) in chars.by_ref() {
+                if c == '"' && !escaped {
+                    end = i + 1;
+                    break;
+                }
+                escaped = c == '\\' && !escaped;
+            }
+            let is_key = line[end..].trim_start().starts_with(':');
+            let token = if is_key { Token::Key } else { Token::String };
+            push(tokens, token, &line[start..end]);
+        } else if c.is_ascii_alphanumeric() || c == '-' {
+            let mut end = line.len();
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')) {
+                    end = i;
+                    break;
+                }
+                chars.next();
+            }
+            let word = &line[start..end];
+            push(tokens, classify_scalar(word), word);
+        } else {
+            push(tokens, Token::Plain, &line[start..start + c.len_utf8()]);
+        }
+    }
+}
+
+fn classify_scalar(scalar: &str) -> Token {
+    match scalar {
+        "" | "|" | ">" | "{}" | "[]" => Token::Plain,
+        "true" | "false" | "null" | "~" => Token::Literal,
+        s if s.parse::<f64>().is_ok() => Token::Number,
+        _ => Token::String,
+    }
+}
+
+fn push(tokens: &mut Vec<(Token, String)>, token: Token, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+
+    // Merge neighbouring tokens of the same kind to keep the rendered tree small
+    match tokens.last_mut() {
+        Some((last, last_text)) if *last == token => last_text.push_str(text),
+        _ => tokens.push((token, text.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, Syntax, Token};
+
+    #[test]
+    fn yaml_key_value() {
+        let tokens = tokenize("  type: string", Syntax::Yaml);
+
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Plain, "  ".to_string()),
+                (Token::Key, "type".to_string()),
+                (Token::Plain, ": ".to_string()),
+                (Token::String, "string".to_string()),
+                (Token::Plain, "\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_key_and_number() {
+        let tokens = tokenize(r#"{"temperature": 21.5}"#, Syntax::Json);
+
+        assert_eq!(
+            tokens,
+            vec![
+                (Token::Plain, "{".to_string()),
+                (Token::Key, "\"temperature\"".to_string()),
+                (Token::Plain, ": ".to_string()),
+                (Token::Number, "21.5".to_string()),
+                (Token::Plain, "}\n".to_string()),
+            ]
+        );
+    }
+}
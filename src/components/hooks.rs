@@ -0,0 +1,10 @@
+use dioxus::prelude::*;
+
+/// Mirrors a prop into a signal, so resources reading the signal rerun when the prop changes.
+pub fn use_synced<T: PartialEq + Clone + 'static>(value: T) -> Signal<T> {
+    let mut signal = use_signal(|| value.clone());
+    if *signal.peek() != value {
+        signal.set(value);
+    }
+    signal
+}
@@ -0,0 +1,179 @@
+use dioxus::prelude::*;
+use serde_json::Value;
+
+use crate::{
+    cache::{fetch_missing, ModelCache},
+    components::{container::Container, nav::View},
+    ngsi::inference::{Candidate, PropertyIndex},
+    smartdata::models::ModelList,
+    ModelData, Pins,
+};
+
+const BUTTON_STYLE: &str = "px-3 rounded-md text-sm border hover:bg-gray-100";
+const CANDIDATES: usize = 10;
+
+/// Ranks the loaded models by how well they fit a pasted entity.
+#[component]
+pub fn Inference(
+    list: ModelList,
+    model_data: Signal<Option<ModelData>>,
+    view: Signal<View>,
+) -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    let pins = consume_context::<Signal<Pins>>();
+    let mut input = use_signal(String::new);
+    let mut status = use_signal(|| None::<String>);
+    let mut loading = use_signal(|| false);
+
+    let load_all = {
+        let list = list.clone();
+        move |_| {
+            let git_ref = pins.read().model.clone();
+            let models: Vec<_> = list
+                .entries
+                .iter()
+                .flat_map(|dmr| {
+                    dmr.data_models.iter().map(|name| ModelData {
+                        repo: dmr.name.clone(),
+                        name: name.clone(),
+                        git_ref: git_ref.clone(),
+                    })
+                })
+                .collect();
+            loading.set(true);
+            status.set(None);
+            spawn(async move {
+                let errors = fetch_missing(cache, models).await;
+                loading.set(false);
+                status.set((!errors.is_empty()).then(|| errors.join(", ")));
+            });
+        }
+    };
+
+    let cache = cache.read();
+    let models = cache.models();
+    let model_count = models.len();
+    let catalog_count = list.model_names().len();
+    let parsed = match input.read().trim() {
+        "" => None,
+        text => Some(serde_json::from_str::<Value>(text)),
+    };
+    let partial =
+        parsed.as_ref().is_some_and(|parsed| parsed.is_ok()) && model_count < catalog_count;
+    let candidates = match &parsed {
+        Some(Ok(entity)) => PropertyIndex::new(models).candidates(entity, CANDIDATES),
+        _ => vec![],
+    };
+
+    rsx!(Container {
+        h1 {
+            class: "font-bold text-slate-950 text-lg",
+            "Which model is this?"
+        },
+        p {
+            class: "text-xs text-slate-500",
+            "Paste an entity in key-values or normalized form, its attribute names and value shapes are compared with the {model_count} loaded models."
+        },
+        div {
+            class: "flex flex-row gap-2 items-center",
+            button {
+                class: BUTTON_STYLE,
+                disabled: loading(),
+                onclick: load_all,
+                "Load all models of the catalog"
+            },
+            if loading() {
+                span {
+                    class: "text-xs text-slate-500",
+                    "Loading, {model_count} of {catalog_count} models are loaded..."
+                }
+            },
+            if let Some(status) = status() {
+                span { class: "text-xs text-slate-500", "{status}" }
+            }
+        },
+        textarea {
+            class: "h-48 w-full rounded-md border p-2 text-xs font-mono",
+            value: "{input}",
+            placeholder: "{{\"id\": \"...\", \"temperature\": 21.5}}",
+            spellcheck: false,
+            oninput: move |event| input.set(event.value())
+        },
+        match parsed {
+            Some(Err(err)) => rsx!(p { class: "text-xs text-red-500", "No valid json: {err}" }),
+            Some(Ok(_)) if candidates.is_empty() => rsx!(p {
+                class: "text-xs text-slate-500",
+                "No loaded model shares an attribute with the entity."
+            }),
+            _ => None,
+        },
+        if partial {
+            p {
+                class: "text-xs text-amber-600",
+                "Only {model_count} of the {catalog_count} models of the catalog are ranked, load all models to rank every one."
+            }
+        },
+        for candidate in candidates {
+            CandidateCard {
+                target: list.repo_of(&candidate.model).map(|repo| ModelData {
+                    repo: repo.name.clone(),
+                    name: candidate.model.clone(),
+                    git_ref: pins.read().model.clone(),
+                }),
+                candidate,
+                model_data,
+                view
+            }
+        }
+    })
+}
+
+#[component]
+fn CandidateCard(
+    candidate: Candidate,
+    target: Option<ModelData>,
+    model_data: Signal<Option<ModelData>>,
+    view: Signal<View>,
+) -> Element {
+    let score = (candidate.score * 100.0).round();
+    let groups = [
+        ("Matched", "text-green-600", &candidate.matched),
+        ("Wrong shape", "text-amber-600", &candidate.mismatched),
+        (
+            "Missing required",
+            "text-red-500",
+            &candidate.missing_required,
+        ),
+        ("Extra", "text-slate-400", &candidate.extra),
+    ]
+    .map(|(label, color, names)| (label, color, names.join(", ")));
+
+    rsx!(div {
+        class: "flex flex-col gap-1 border-t pt-2",
+        div {
+            class: "flex flex-row gap-2 items-center",
+            span { class: "w-12 text-sm font-medium text-slate-900", "{score}%" },
+            button {
+                class: "text-sm text-sky-700 hover:underline disabled:no-underline disabled:text-slate-900",
+                disabled: target.is_none(),
+                onclick: move |_| {
+                    if let Some(target) = target.clone() {
+                        model_data.set(Some(target));
+                        view.set(View::Model);
+                    }
+                },
+                "{candidate.model}"
+            },
+            span { class: "text-xs text-slate-400", "dataModel.{candidate.repo}" }
+        },
+        for (label, color, names) in groups {
+            if !names.is_empty() {
+                p {
+                    class: "text-xs pl-14",
+                    span { class: "text-slate-500", "{label}: " },
+                    span { class: color, "{names}" }
+                }
+            }
+        }
+    })
+}
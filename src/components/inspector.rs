@@ -0,0 +1,113 @@
+use dioxus::prelude::*;
+
+use crate::{
+    cache::ModelCache,
+    components::highlight::{Highlighted, Syntax},
+    smartdata::models::Property,
+};
+
+#[component]
+pub fn Inspector(selected_model: String, inspected: Signal<Option<String>>) -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    let cache = cache.read();
+
+    let name = inspected()?;
+    let model = cache.get(&selected_model)?;
+    let property = model.property(&name)?;
+
+    let raw_property = model.raw_property(&name).unwrap_or_default();
+    let definitions = cache.definitions_of(&selected_model, &name);
+
+    rsx!(div {
+        class: "w-96 flex flex-col gap-2 border-l pl-2",
+        div {
+            class: "flex flex-row",
+            h1 {
+                class: "font-bold text-slate-950",
+                "{property.name}"
+            },
+            button {
+                class: "ml-auto text-xs text-slate-500 hover:text-slate-900",
+                onclick: move |_| inspected.set(None),
+                "✕"
+            }
+        },
+        p {
+            class: "text-xs text-slate-500",
+            "{property.description.clone().unwrap_or_default()}"
+        },
+        PropertyTable { property: property.clone(), differences: vec![] },
+        h2 {
+            class: "text-sm font-medium text-slate-900",
+            "Source schema"
+        },
+        a {
+            class: "text-xs text-blue-400 hover:underline break-all",
+            href: model.schema.clone(),
+            "{model.schema}"
+        },
+        Highlighted { code: raw_property, syntax: Syntax::Yaml },
+        h2 {
+            class: "text-sm font-medium text-slate-900",
+            "Defined in other models"
+        },
+        if definitions.is_empty() {
+            p {
+                class: "text-xs text-slate-500",
+                "No other loaded model defines {name}."
+            }
+        },
+        for (other_model, other_property) in definitions {
+            div {
+                class: "flex flex-col gap-1",
+                h3 {
+                    class: "text-xs font-medium text-slate-900",
+                    "{other_model.repo}/{other_model.name}"
+                },
+                PropertyTable {
+                    property: other_property.clone(),
+                    differences: property.differences(other_property)
+                }
+            }
+        }
+    })
+}
+
+#[component]
+fn PropertyTable(property: Property, differences: Vec<&'static str>) -> Element {
+    let enums = property.enums.clone().map(|enums| enums.join(", "));
+    let constraints = property
+        .constraints()
+        .iter()
+        .map(|(keyword, value)| format!("{keyword}: {value}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let rows = [
+        ("type", property.typ.clone()),
+        ("format", property.format.clone()),
+        ("x-ngsi type", property.ngsi_type().map(str::to_string)),
+        ("x-ngsi model", property.ngsi_model().map(str::to_string)),
+        ("units", property.units().map(str::to_string)),
+        ("enum", enums),
+        ("constraints", Some(constraints).filter(|c| !c.is_empty())),
+        ("required", Some(property.required.to_string())),
+    ];
+
+    rsx!(table {
+        class: "text-xs",
+        for (label, value) in rows {
+            tr {
+                class: if differences.contains(&label) { "bg-amber-100" } else { "" },
+                td {
+                    class: "pr-4 align-top font-medium text-slate-900",
+                    "{label}"
+                },
+                td {
+                    class: "text-slate-500 break-all",
+                    "{value.unwrap_or_else(|| String::from(\"-\"))}"
+                }
+            }
+        }
+    })
+}
@@ -1,15 +1,70 @@
 use dioxus::prelude::*;
 
 use crate::{
-    components::{cards::RepoCard, container::Container},
-    smartdata::models::ModelList,
-    ModelData,
+    cache::{fetch_missing, ModelCache},
+    components::{
+        cards::RepoCard, container::Container, pins::PinSettings, workspace::WorkspaceMembers,
+    },
+    smartdata::{
+        derivation::{Derivation, Source},
+        models::ModelList,
+        refs::short_ref,
+    },
+    ModelData, Pins,
 };
 
 #[component]
 pub fn FilteredList(list: ModelList, model_data: Signal<Option<ModelData>>) -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    let pins = consume_context::<Signal<Pins>>();
     let mut filter = use_signal(|| String::from(""));
-    let filtered_entries = list.get_filtered_entries(&filter());
+    let mut source = use_signal(|| None::<Source>);
+    let mut loading = use_signal(|| false);
+    let mut errors = use_signal(Vec::<String>::new);
+
+    let mut filtered_entries = list.get_filtered_entries(&filter());
+    // The source is only known for loaded models, all others are hidden while filtering
+    if let Some(source) = source() {
+        let cache = cache.read();
+        let is_derived = |name: &str| {
+            cache
+                .get(name)
+                .is_some_and(|model| Derivation::parse(&model.derived_from).source == source)
+        };
+        filtered_entries = filtered_entries
+            .into_iter()
+            .filter_map(|mut dmr| {
+                dmr.data_models.retain(|name| is_derived(name));
+                (!dmr.data_models.is_empty()).then_some(dmr)
+            })
+            .collect();
+    }
+
+    let model_count = list.model_names().len();
+    let loaded_count = cache.read().models().len();
+    let load_all = {
+        let list = list.clone();
+        move |_| {
+            let git_ref = pins.read().model.clone();
+            let models = list
+                .entries
+                .iter()
+                .flat_map(|dmr| {
+                    dmr.data_models.iter().map(|name| ModelData {
+                        repo: dmr.name.clone(),
+                        name: name.clone(),
+                        git_ref: git_ref.clone(),
+                    })
+                })
+                .collect();
+            loading.set(true);
+            errors.set(vec![]);
+            spawn(async move {
+                errors.set(fetch_missing(cache, models).await);
+                loading.set(false);
+            });
+        }
+    };
     let filtered_entries_rendered = filtered_entries.iter().map(|data_model_repo| {
         rsx!(RepoCard {
             data_model_repo: data_model_repo.clone(),
@@ -25,6 +80,12 @@ pub fn FilteredList(list: ModelList, model_data: Signal<Option<ModelData>>) -> E
                 class: "font-bold text-slate-950 text-lg",
                 "Smart Model Selection"
             },
+            p {
+                class: "text-xs text-slate-400",
+                "Official list @ {short_ref(&list.git_ref)}, updated {list.date}"
+            },
+            PinSettings {},
+            WorkspaceMembers { model_data },
             // FilterInput and List
             div {
                 class: "relative",
@@ -54,6 +115,41 @@ pub fn FilteredList(list: ModelList, model_data: Signal<Option<ModelData>>) -> E
                     oninput: move |event| filter.set(event.value())
                 },
             }
+            // Source filter
+            div {
+                class: "flex flex-row gap-2 items-center text-xs text-slate-500",
+                select {
+                    class: "border",
+                    onchange: move |event| source.set(Source::from_name(&event.value())),
+                    option { value: "", "Derived from any source" },
+                    for s in Source::array() {
+                        option {
+                            value: "{s.str()}",
+                            selected: source() == Some(s),
+                            "{s.str()}"
+                        }
+                    }
+                },
+                span { "{loaded_count}/{model_count} loaded" },
+                button {
+                    class: "ml-auto px-2 rounded-md border hover:bg-gray-100",
+                    disabled: loading(),
+                    onclick: load_all,
+                    if loading() { "Loading..." } else { "Load all" }
+                }
+            },
+            if source().is_some() && loaded_count < model_count {
+                p {
+                    class: "text-xs text-slate-400",
+                    "Models which are not loaded are hidden, their source is unknown."
+                }
+            },
+            if !errors.read().is_empty() {
+                p {
+                    class: "text-xs text-red-500",
+                    "{errors.read().len()} models failed to load: {errors.read().join(\", \")}"
+                }
+            },
             // List
             div {
                 class: "w-full divide-y border rounded-lg shadow-sm px-3 py-1 mb-1
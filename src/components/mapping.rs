@@ -0,0 +1,266 @@
+use std::path::PathBuf;
+
+use dioxus::prelude::*;
+
+use crate::{
+    components::highlight::{Highlighted, Syntax},
+    ngsi::{
+        mapping::{hint, Conversion, CsvMapping, CsvTable},
+        Format,
+    },
+    smartdata::models::ParsedModel,
+};
+
+const INPUT_STYLE: &str = "h-6 w-96 rounded-md border px-2 text-xs";
+const BUTTON_STYLE: &str = "px-2 rounded-md text-xs border hover:bg-gray-100";
+const SELECT_STYLE: &str = "border text-xs text-slate-500";
+/// Errors beyond this are only counted.
+const SHOWN_ERRORS: usize = 20;
+
+/// Maps the columns of a csv file to the properties of a model and converts its rows.
+#[component]
+pub fn CsvMapper(model: ParsedModel) -> Element {
+    let mut mapping = use_signal(|| CsvMapping::new(&model));
+    let mut table = use_signal(|| None::<CsvTable>);
+    let mut csv_path = use_signal(String::new);
+    let mut mapping_path = use_signal(String::new);
+    let mut status = use_signal(|| None::<Result<String, String>>);
+    // Start over, when another model is selected
+    if mapping.peek().model != model.name {
+        mapping.set(CsvMapping::new(&model));
+    }
+
+    let load_csv = {
+        let model = model.clone();
+        move |_| {
+            let delimiter = mapping.read().delimiter;
+            status.set(Some(
+                match CsvTable::load(&PathBuf::from(csv_path()), delimiter) {
+                    Ok(loaded) => {
+                        mapping.write().suggest(&model, &loaded.headers);
+                        let message = format!("Loaded {} rows", loaded.rows.len());
+                        table.set(Some(loaded));
+                        Ok(message)
+                    }
+                    Err(err) => Err(err.to_string()),
+                },
+            ));
+        }
+    };
+
+    let open = {
+        let name = model.name.clone();
+        move |_| {
+            status.set(Some(
+                match CsvMapping::load(&PathBuf::from(mapping_path())) {
+                    Ok(opened) if opened.model != name => {
+                        Err(format!("The mapping is made for {}", opened.model))
+                    }
+                    Ok(opened) => {
+                        mapping.set(opened);
+                        Ok(String::from("Opened the mapping"))
+                    }
+                    Err(err) => Err(err.to_string()),
+                },
+            ));
+        }
+    };
+
+    let save = move |_| {
+        let path = PathBuf::from(mapping_path());
+        status.set(Some(match mapping.write().save(&path) {
+            Ok(()) => Ok(format!("Saved to {}", path.display())),
+            Err(err) => Err(err.to_string()),
+        }));
+    };
+
+    let current = mapping.read().clone();
+    let rendered_table = table().map(|table| {
+        let columns = table.headers.iter().enumerate().map(|(i, header)| {
+            let sample = table
+                .rows
+                .first()
+                .and_then(|row| row.get(i))
+                .cloned()
+                .unwrap_or_default();
+            let mapped = current.column(header).cloned();
+            let property = mapped
+                .as_ref()
+                .and_then(|mapped| model.property(&mapped.property));
+            let mut conversions = property.map(Conversion::suggestions).unwrap_or_default();
+            if let Some(mapped) = &mapped {
+                if !conversions.contains(&mapped.conversion) {
+                    conversions.push(mapped.conversion);
+                }
+            }
+            let hint = property.map(hint).unwrap_or_default();
+
+            rsx!(tr {
+                td { class: "pr-4 py-1 text-xs font-medium text-slate-900", "{header}" },
+                td { class: "pr-4 py-1 text-xs text-slate-400 font-mono", "{sample}" },
+                td {
+                    class: "pr-4 py-1",
+                    select {
+                        class: SELECT_STYLE,
+                        onchange: {
+                            let header = header.clone();
+                            let model = model.clone();
+                            move |event: FormEvent| {
+                                let name = event.value();
+                                mapping.write().map_column(&header, model.property(&name));
+                            }
+                        },
+                        option { value: "", "not mapped" },
+                        for p in model.properties.iter() {
+                            option {
+                                value: "{p.name}",
+                                selected: mapped.as_ref().is_some_and(|m| m.property == p.name),
+                                "{p.name}"
+                            }
+                        }
+                    }
+                },
+                td {
+                    class: "pr-4 py-1",
+                    if !conversions.is_empty() {
+                        select {
+                            class: SELECT_STYLE,
+                            onchange: {
+                                let header = header.clone();
+                                move |event: FormEvent| {
+                                    if let Some(c) = Conversion::from_name(&event.value()) {
+                                        mapping.write().set_conversion(&header, c);
+                                    }
+                                }
+                            },
+                            for c in conversions {
+                                option {
+                                    value: "{c.str()}",
+                                    selected: mapped.as_ref().is_some_and(|m| m.conversion == c),
+                                    "{c.str()}"
+                                }
+                            }
+                        }
+                    }
+                },
+                td { class: "py-1 text-xs text-slate-400", "{hint}" }
+            })
+        });
+
+        let rendered_results = match current.convert(&model, &table) {
+            Ok(results) => {
+                let valid = results.iter().filter(|r| r.errors.is_empty()).count();
+                let errors: Vec<_> = results
+                    .iter()
+                    .flat_map(|r| {
+                        r.errors
+                            .iter()
+                            .map(|e| format!("row {}: {} {}", r.row, e.property, e.message))
+                    })
+                    .collect();
+                let hidden = errors.len().saturating_sub(SHOWN_ERRORS);
+                let preview = results
+                    .first()
+                    .map(|r| serde_json::to_string_pretty(&r.entity).unwrap_or_default());
+
+                rsx!(
+                    p {
+                        class: if valid == results.len() { "text-xs text-green-600" } else { "text-xs text-red-500" },
+                        "{valid} of {results.len()} rows are valid entities."
+                    },
+                    for error in errors.into_iter().take(SHOWN_ERRORS) {
+                        p { class: "text-xs text-red-500", "{error}" }
+                    },
+                    if hidden > 0 {
+                        p { class: "text-xs text-slate-500", "and {hidden} more errors" }
+                    },
+                    if let Some(preview) = preview {
+                        Highlighted { code: preview, syntax: Syntax::Json }
+                    }
+                )
+            }
+            Err(err) => rsx!(p { class: "text-xs text-red-500", "{err}" }),
+        };
+
+        rsx!(
+            table {
+                tr {
+                    for heading in ["Column", "First row", "Property", "Conversion", "Expected"] {
+                        th { class: "pr-4 text-left text-xs font-normal text-slate-500", "{heading}" }
+                    }
+                },
+                {columns}
+            },
+            {rendered_results}
+        )
+    });
+
+    rsx!(div {
+        class: "flex flex-col gap-2",
+        div {
+            class: "flex flex-row gap-2 items-center",
+            input {
+                class: INPUT_STYLE,
+                value: "{csv_path}",
+                placeholder: "Path of the csv file",
+                spellcheck: false,
+                oninput: move |event| csv_path.set(event.value())
+            },
+            input {
+                class: "h-6 w-8 rounded-md border text-center text-xs",
+                value: "{current.delimiter}",
+                title: "Delimiter",
+                maxlength: 1,
+                oninput: move |event| {
+                    if let Some(delimiter) = event.value().chars().next() {
+                        mapping.write().delimiter = delimiter;
+                    }
+                }
+            },
+            button { class: BUTTON_STYLE, onclick: load_csv, "Load" }
+        },
+        div {
+            class: "flex flex-row gap-2 items-center",
+            input {
+                class: INPUT_STYLE,
+                value: "{mapping_path}",
+                placeholder: "Path of the mapping file, the CLI applies it in batch",
+                spellcheck: false,
+                oninput: move |event| mapping_path.set(event.value())
+            },
+            button { class: BUTTON_STYLE, onclick: open, "Open" },
+            button { class: BUTTON_STYLE, onclick: save, "Save" }
+        },
+        div {
+            class: "flex flex-row gap-2 items-center",
+            input {
+                class: INPUT_STYLE,
+                value: "{current.id}",
+                title: "Id template, {{column}} is the value of a column and {{row}} the row number",
+                spellcheck: false,
+                oninput: move |event| mapping.write().id = event.value()
+            },
+            select {
+                class: SELECT_STYLE,
+                onchange: move |event| {
+                    if let Some(f) = Format::from_name(&event.value()) {
+                        mapping.write().format = f;
+                    }
+                },
+                for f in Format::array() {
+                    option {
+                        value: "{f.str()}",
+                        selected: current.format == f,
+                        "{f.str()}"
+                    }
+                }
+            }
+        },
+        match status() {
+            Some(Ok(message)) => rsx!(p { class: "text-xs text-green-600", "{message}" }),
+            Some(Err(error)) => rsx!(p { class: "text-xs text-red-500", "{error}" }),
+            None => None,
+        },
+        {rendered_table}
+    })
+}
@@ -1,5 +1,21 @@
+pub mod broker;
+pub mod builder;
 pub mod cards;
 pub mod codeview;
+pub mod compare;
+pub mod conformance;
 pub mod container;
+pub mod converter;
+pub mod diff;
+pub mod graph;
+pub mod highlight;
+pub mod hooks;
+pub mod inference;
+pub mod inspector;
 pub mod list;
+pub mod mapping;
 pub mod model;
+pub mod nav;
+pub mod pins;
+pub mod profiles;
+pub mod workspace;
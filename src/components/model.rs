@@ -2,15 +2,119 @@ use dioxus::prelude::*;
 
 use crate::{
     cache::ModelCache,
-    components::container::Container,
-    smartdata::models::{GeoProperty, ParsedModel, Property},
+    components::{
+        builder::EntityBuilder,
+        codeview::Codeview,
+        container::Container,
+        converter::Converter,
+        highlight::{Highlighted, Syntax},
+        hooks::use_synced,
+        inspector::Inspector,
+        mapping::CsvMapper,
+        pins::RefInput,
+    },
+    smartdata::{
+        derivation::{Derivation, DerivationTree},
+        examples::{fetch_readme, Example},
+        models::{GeoProperty, ParsedModel, Property},
+        refs::short_ref,
+    },
+    workspace::{Workspace, WorkspaceEntry},
+    ModelData, ShowError,
 };
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tab {
+    Properties,
+    Builder,
+    Mapping,
+    Converter,
+    Code,
+    Yaml,
+    Examples,
+    Notes,
+    Metadata,
+}
+
+impl Tab {
+    fn str(&self) -> &'static str {
+        match self {
+            Tab::Properties => "Properties",
+            Tab::Builder => "Entity builder",
+            Tab::Mapping => "CSV mapping",
+            Tab::Converter => "Converter",
+            Tab::Code => "Code",
+            Tab::Yaml => "model.yaml",
+            Tab::Examples => "Examples",
+            Tab::Notes => "Notes",
+            Tab::Metadata => "Metadata",
+        }
+    }
+
+    fn array() -> [Tab; 9] {
+        [
+            Tab::Properties,
+            Tab::Builder,
+            Tab::Mapping,
+            Tab::Converter,
+            Tab::Code,
+            Tab::Yaml,
+            Tab::Examples,
+            Tab::Notes,
+            Tab::Metadata,
+        ]
+    }
+}
+
 #[component]
-pub fn Model(model: ParsedModel) -> Element {
+pub fn Model(model: ParsedModel, model_data: Signal<Option<ModelData>>) -> Element {
     let name = model.name.clone();
     let url = model.url.clone();
     let description = model.description.clone();
+    let repo = model.repo.clone();
+    let git_ref = model.git_ref.clone();
+    let mut tab = use_signal(|| Tab::Properties);
+    let mut compared = consume_context::<Signal<Vec<String>>>();
+    let is_compared = compared.read().contains(&name);
+    let mut workspace = consume_context::<Signal<Workspace>>();
+    let in_workspace = workspace.read().contains(&name);
+
+    let rendered_tab = match tab() {
+        Tab::Properties => rsx!(if !name.is_empty() {
+            Properties { selected_model: name.clone() }
+        } else {
+            p { "Loading..." }
+        }),
+        Tab::Builder => rsx!(EntityBuilder {
+            selected_model: name.clone()
+        }),
+        Tab::Mapping => rsx!(CsvMapper {
+            model: model.clone()
+        }),
+        Tab::Converter => rsx!(Converter {
+            model: model.clone()
+        }),
+        Tab::Code => rsx!(Codeview {
+            selected_model: name.clone()
+        }),
+        Tab::Yaml => rsx!(Highlighted {
+            code: model.raw.clone(),
+            syntax: Syntax::Yaml
+        }),
+        Tab::Examples => rsx!(Examples {
+            repo: model.repo.clone(),
+            name: name.clone(),
+            git_ref: model.resolved_ref.clone()
+        }),
+        Tab::Notes => rsx!(Notes {
+            repo: model.repo.clone(),
+            name: name.clone(),
+            git_ref: model.resolved_ref.clone()
+        }),
+        Tab::Metadata => rsx!(Metadata {
+            model: model.clone()
+        }),
+    };
 
     rsx! {
         Container {
@@ -23,8 +127,43 @@ pub fn Model(model: ParsedModel) -> Element {
                         class: "font-bold text-slate-950 text-lg",
                         "{name}"
                     },
+                    button {
+                        class: "my-auto ml-auto text-xs text-slate-500 hover:text-slate-900",
+                        onclick: {
+                            let name = name.clone();
+                            move |_| {
+                                if is_compared {
+                                    compared.write().retain(|n| *n != name);
+                                } else {
+                                    compared.write().push(name.clone());
+                                }
+                            }
+                        },
+                        if is_compared { "(- compare)" } else { "(+ compare)" }
+                    },
+                    if !model.repo.is_empty() {
+                        button {
+                            class: "my-auto ml-2 text-xs text-slate-500 hover:text-slate-900",
+                            onclick: {
+                                let entry = WorkspaceEntry {
+                                    repo: model.repo.clone(),
+                                    model: name.clone(),
+                                    git_ref: model.git_ref.clone(),
+                                    profile: None,
+                                };
+                                move |_| {
+                                    if in_workspace {
+                                        workspace.write().remove(&entry.model);
+                                    } else {
+                                        workspace.write().add(entry.clone());
+                                    }
+                                }
+                            },
+                            if in_workspace { "(- workspace)" } else { "(+ workspace)" }
+                        }
+                    },
                     a {
-                        class: "my-auto ml-auto text-xs text-blue-400 hover:underline",
+                        class: "my-auto ml-2 text-xs text-blue-400 hover:underline",
                         href: url,
                         "(link)"
                     }
@@ -33,73 +172,267 @@ pub fn Model(model: ParsedModel) -> Element {
                     class: "text-xs text-slate-500",
                     "{description}"
                 },
+                if !model.repo.is_empty() {
+                    div {
+                        class: "flex flex-col gap-1 text-xs text-slate-500",
+                        p {
+                            class: "text-slate-400",
+                            "{repo} @ {git_ref}",
+                            if model.resolved_ref != model.git_ref {
+                                " ({short_ref(&model.resolved_ref)})"
+                            }
+                        },
+                        RefInput {
+                            label: "Ref",
+                            value: model.git_ref.clone(),
+                            onpin: {
+                                let (repo, name) = (model.repo.clone(), name.clone());
+                                move |git_ref| {
+                                    model_data.set(Some(ModelData {
+                                        repo: repo.clone(),
+                                        name: name.clone(),
+                                        git_ref,
+                                    }))
+                                }
+                            }
+                        }
+                    }
+                }
             }
-            hr {},
-            // Properties
-            if !name.is_empty() {
-                Properties { selected_model: name}
-            } else {
-                p { "Loading..." }
-            }
+            // Tabs
+            div {
+                class: "flex flex-row gap-1 border-b",
+                for t in Tab::array() {
+                    button {
+                        class: if tab() == t {
+                            "px-2 py-1 text-sm font-medium text-slate-900 border-b-2 border-slate-900"
+                        } else {
+                            "px-2 py-1 text-sm text-slate-500 hover:text-slate-900"
+                        },
+                        onclick: move |_| tab.set(t),
+                        "{t.str()}"
+                    }
+                }
+            },
+            {rendered_tab}
         }
     }
 }
 
+#[component]
+fn Examples(repo: String, name: String, git_ref: String) -> Element {
+    let key = use_synced((repo, name, git_ref));
+    let examples = use_resource(move || async move {
+        let (repo, name, git_ref) = key();
+        Example::fetch_all(&repo, &name, &git_ref).await
+    });
+
+    let rendered_examples = match &*examples.read() {
+        Some(Ok(examples)) if examples.is_empty() => rsx!(p {
+            class: "text-xs text-slate-500",
+            "This model has no examples."
+        }),
+        Some(Ok(examples)) => rsx!(for example in examples.iter() {
+            div {
+                class: "flex flex-col gap-1",
+                h2 {
+                    class: "text-sm font-medium text-slate-900",
+                    "{example.file}"
+                },
+                Highlighted {
+                    code: example.body.clone(),
+                    syntax: Syntax::from_file(&example.file)
+                }
+            }
+        }),
+        Some(Err(err)) => rsx!(ShowError {
+            error: err.to_string()
+        }),
+        None => rsx!(p { "Loading..." }),
+    };
+
+    rsx!({ rendered_examples })
+}
+
+#[component]
+fn Notes(repo: String, name: String, git_ref: String) -> Element {
+    let key = use_synced((repo, name, git_ref));
+    let readme = use_resource(move || async move {
+        let (repo, name, git_ref) = key();
+        fetch_readme(&repo, &name, &git_ref).await
+    });
+
+    let rendered_readme = match &*readme.read() {
+        Some(Ok(readme)) => rsx!(pre {
+            class: "text-xs text-slate-600 whitespace-pre-wrap",
+            "{readme}"
+        }),
+        Some(Err(err)) => rsx!(ShowError {
+            error: err.to_string()
+        }),
+        None => rsx!(p { "Loading..." }),
+    };
+
+    rsx!({ rendered_readme })
+}
+
+#[component]
+fn Metadata(model: ParsedModel) -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    let models: Vec<_> = cache.read().models().into_iter().cloned().collect();
+    let tree = DerivationTree::new(&models);
+    let ancestors = tree.ancestors(&model.name);
+    let descendants = tree.descendants(&model.name);
+    let source = Derivation::parse(&model.derived_from).source;
+
+    let rows = [
+        ("Version", model.version),
+        ("Type", model.typ),
+        ("Tags", model.tags),
+        ("Derived from", model.derived_from),
+        ("License", model.license_url),
+        ("Schema", model.schema),
+        ("Disclaimer", model.disclaimer),
+    ];
+
+    rsx!(table {
+        class: "text-sm",
+        for (label, value) in rows {
+            tr {
+                td {
+                    class: "pr-4 py-1 align-top font-medium text-slate-900",
+                    "{label}"
+                },
+                td {
+                    class: "py-1 text-slate-500 break-all",
+                    if value.starts_with("http") {
+                        a {
+                            class: "text-blue-400 hover:underline",
+                            href: value.clone(),
+                            "{value}"
+                        }
+                    } else {
+                        "{value}"
+                    }
+                }
+            }
+        }
+    },
+    h2 {
+        class: "text-sm font-medium text-slate-900",
+        "Derivation"
+    },
+    p {
+        class: "text-xs text-slate-500",
+        "Source: {source.str()}"
+    },
+    p {
+        class: "text-xs text-slate-500",
+        if ancestors.is_empty() {
+            "Derives from no other loaded model."
+        } else {
+            "Ancestors: {ancestors.join(\" → \")}"
+        }
+    },
+    if descendants.is_empty() {
+        p {
+            class: "text-xs text-slate-500",
+            "No loaded model derives from {model.name}."
+        }
+    } else {
+        ul {
+            class: "text-xs text-slate-500",
+            for (depth, name) in descendants {
+                li {
+                    style: "padding-left: {depth}rem",
+                    "{name}"
+                }
+            }
+        }
+    })
+}
+
 #[component]
 fn Properties(selected_model: String) -> Element {
     let mut cache = consume_context::<Signal<ModelCache>>();
+    let mut inspected = use_signal(|| None);
 
     let rendered_model = match cache.read().get(&selected_model) {
         Some(model) => rsx!(
-            h1 {
-                class: "",
-                "Properties"
-            },
-            for (i, prop) in model.properties.iter().enumerate() {
-                div {
-                    class: "flex flex-row gap-2",
-                    label {
-                        class: "text-sm text-slate-500",
-                        "{prop.name}"
-                    },
-                    { prop.maybe_combobox() },
+            div {
+                class: "flex flex-col gap-1",
+                h1 {
+                    class: "",
+                    "Properties"
+                },
+                for (i, prop) in model.properties.iter().enumerate() {
                     div {
-                        class: "ml-auto flex flex-row gap-2",
-                        if prop.required {
-                            span {
-                                class: "mr-4 text-xs text-red-400",
-                                "(required)"
-                            }
+                        class: if inspected.read().as_ref() == Some(&prop.name) {
+                            "flex flex-row gap-2 rounded-md bg-green-100 hover:cursor-pointer"
+                        } else {
+                            "flex flex-row gap-2 rounded-md hover:bg-gray-100 hover:cursor-pointer"
                         },
-                        input {
-                            class: "",
-                            r#type: "checkbox",
-                            checked: prop.checked,
-                            onchange: {
-                                let selected_model = selected_model.clone();
-                                move |_| cache.write().flip_checked(&selected_model, i)
-                            },
+                        onclick: {
+                            let name = prop.name.clone();
+                            move |_| inspected.set(Some(name.clone()))
+                        },
+                        label {
+                            class: "text-sm text-slate-500",
+                            "{prop.name}"
                         },
+                        { prop.maybe_combobox(&selected_model, i) },
+                        div {
+                            class: "ml-auto flex flex-row gap-2",
+                            if prop.required {
+                                span {
+                                    class: "mr-4 text-xs text-red-400",
+                                    "(required)"
+                                }
+                            },
+                            input {
+                                class: "",
+                                r#type: "checkbox",
+                                checked: prop.checked,
+                                onclick: move |event| event.stop_propagation(),
+                                onchange: {
+                                    let selected_model = selected_model.clone();
+                                    move |_| cache.write().flip_checked(&selected_model, i)
+                                },
+                            },
+                        }
                     }
                 }
-            }
+            },
+            Inspector { selected_model: selected_model.clone(), inspected }
         ),
         None => rsx!("Loading..."),
     };
 
-    rsx!({ rendered_model })
+    rsx!(div {
+        class: "flex flex-row gap-2",
+        { rendered_model }
+    })
 }
 
 impl Property {
-    pub fn maybe_combobox(&self) -> Option<Element> {
+    pub fn maybe_combobox(&self, selected_model: &str, index: usize) -> Option<Element> {
         // TODO: missing other one_of combobox options
         if self.one_of.is_some() && self.name == "location" {
+            let mut cache = consume_context::<Signal<ModelCache>>();
+            let variant = self.variant.clone().unwrap_or_default();
+            let selected_model = selected_model.to_string();
+
             return Some(rsx! {
                 select {
                     class: "border text-xs text-slate-500",
+                    onclick: move |event| event.stop_propagation(),
+                    onchange: move |event| {
+                        cache.write().set_variant(&selected_model, index, event.value())
+                    },
                     for geo_prop in GeoProperty::array() {
                         option {
                             value: "{geo_prop.str()}",
+                            selected: variant == geo_prop.str(),
                              "{geo_prop.str()}"
                         }
                     }
@@ -0,0 +1,62 @@
+use dioxus::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum View {
+    Model,
+    Compare,
+    Diff,
+    Profiles,
+    Workspace,
+    Graph,
+    Broker,
+    Conformance,
+    Inference,
+}
+
+impl View {
+    pub fn str(&self) -> &'static str {
+        match self {
+            View::Model => "Model",
+            View::Compare => "Compare",
+            View::Diff => "Diff",
+            View::Profiles => "Profiles",
+            View::Workspace => "Workspace",
+            View::Graph => "Graph",
+            View::Broker => "Broker",
+            View::Conformance => "Conformance",
+            View::Inference => "Which model?",
+        }
+    }
+
+    pub fn array() -> [View; 9] {
+        [
+            View::Model,
+            View::Compare,
+            View::Diff,
+            View::Profiles,
+            View::Workspace,
+            View::Graph,
+            View::Broker,
+            View::Conformance,
+            View::Inference,
+        ]
+    }
+}
+
+#[component]
+pub fn Nav(view: Signal<View>) -> Element {
+    rsx!(nav {
+        class: "flex flex-row gap-1 mx-2 mt-2",
+        for v in View::array() {
+            button {
+                class: if view() == v {
+                    "px-3 py-1 rounded-md text-sm font-medium bg-slate-900 text-white"
+                } else {
+                    "px-3 py-1 rounded-md text-sm text-slate-500 hover:bg-gray-100"
+                },
+                onclick: move |_| view.set(v),
+                "{v.str()}"
+            }
+        }
+    })
+}
@@ -0,0 +1,56 @@
+use dioxus::prelude::*;
+
+use crate::Pins;
+
+#[component]
+pub fn PinSettings() -> Element {
+    let mut pins = consume_context::<Signal<Pins>>();
+    let Pins { list, model } = pins();
+
+    rsx!(div {
+        class: "flex flex-col gap-1 text-xs text-slate-500",
+        RefInput {
+            label: "List",
+            value: list,
+            onpin: move |git_ref| pins.write().list = git_ref
+        },
+        RefInput {
+            label: "Models",
+            value: model,
+            onpin: move |git_ref| pins.write().model = git_ref
+        },
+    })
+}
+
+/// Input for a branch, tag, commit sha or date (`YYYY-MM-DD`) which is only applied on pinning.
+#[component]
+pub fn RefInput(label: &'static str, value: String, onpin: EventHandler<String>) -> Element {
+    let mut draft = use_signal(|| value.clone());
+    // Reset the draft, when the pinned value changes from the outside
+    let mut pinned = use_signal(|| value.clone());
+    if *pinned.peek() != value {
+        pinned.set(value.clone());
+        draft.set(value.clone());
+    }
+
+    rsx!(div {
+        class: "flex flex-row gap-2 items-center",
+        span {
+            class: "w-12",
+            "{label}"
+        },
+        input {
+            class: "h-6 w-40 rounded-md border px-2 text-xs",
+            value: "{draft}",
+            placeholder: "branch, tag, sha or date",
+            spellcheck: false,
+            oninput: move |event| draft.set(event.value())
+        },
+        button {
+            class: "px-2 rounded-md border hover:bg-gray-100",
+            disabled: draft() == value,
+            onclick: move |_| onpin.call(draft()),
+            "Pin"
+        }
+    })
+}
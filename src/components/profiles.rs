@@ -0,0 +1,195 @@
+use std::path::PathBuf;
+
+use dioxus::prelude::*;
+
+use crate::{
+    cache::{fetch_missing, ModelCache},
+    components::container::Container,
+    profile::{profiles_dir, Profile},
+    ModelData, Pins,
+};
+
+const INPUT_STYLE: &str = "h-8 w-64 rounded-md border px-2 text-sm";
+const BUTTON_STYLE: &str = "px-3 rounded-md text-sm border hover:bg-gray-100";
+
+type Status = Option<Result<String, String>>;
+
+#[component]
+pub fn Profiles() -> Element {
+    let cache = consume_context::<Signal<ModelCache>>();
+    // Bumped after every change of the profiles directory, so it is read again
+    let mut revision = use_signal(|| 0);
+    let mut status = use_signal(|| None);
+    let mut new_name = use_signal(|| String::from(""));
+    let mut path = use_signal(|| String::from(""));
+
+    let _ = revision();
+    let profiles = Profile::list_saved();
+    let profiles_dir = profiles_dir();
+
+    let save_selection = move |_| {
+        let name = new_name();
+        if name.is_empty() {
+            return;
+        }
+
+        let profile = cache.read().record_profile(&name);
+        status.set(Some(match profile.save() {
+            Ok(path) => Ok(format!("Saved {name} to {}", path.display())),
+            Err(err) => Err(err.to_string()),
+        }));
+        revision += 1;
+    };
+
+    let import = move |_| {
+        let result = Profile::load(&PathBuf::from(path())).and_then(|profile| {
+            profile.save()?;
+            Ok(profile)
+        });
+        status.set(Some(match result {
+            Ok(profile) => Ok(format!("Imported {}", profile.name)),
+            Err(err) => Err(err.to_string()),
+        }));
+        revision += 1;
+    };
+
+    rsx!(Container {
+        h1 {
+            class: "font-bold text-slate-950 text-lg",
+            "Profiles"
+        },
+        p {
+            class: "text-xs text-slate-400",
+            "Saved in {profiles_dir.display()}"
+        },
+        div {
+            class: "flex flex-row gap-2",
+            input {
+                class: INPUT_STYLE,
+                value: "{new_name}",
+                placeholder: "Name, e.g. minimal-sensor",
+                spellcheck: false,
+                oninput: move |event| new_name.set(event.value())
+            },
+            button {
+                class: BUTTON_STYLE,
+                onclick: save_selection,
+                "Save current selection"
+            }
+        },
+        div {
+            class: "flex flex-row gap-2",
+            input {
+                class: INPUT_STYLE,
+                value: "{path}",
+                placeholder: "Path for import and export",
+                spellcheck: false,
+                oninput: move |event| path.set(event.value())
+            },
+            button {
+                class: BUTTON_STYLE,
+                onclick: import,
+                "Import"
+            }
+        },
+        match status() {
+            Some(Ok(message)) => rsx!(p { class: "text-xs text-green-600", "{message}" }),
+            Some(Err(error)) => rsx!(p { class: "text-xs text-red-500", "{error}" }),
+            None => None,
+        },
+        if profiles.is_empty() {
+            p {
+                class: "text-sm text-slate-500",
+                "No saved profiles."
+            }
+        },
+        table {
+            class: "text-sm",
+            for profile in profiles {
+                ProfileRow { profile, path, status, revision }
+            }
+        }
+    })
+}
+
+#[component]
+fn ProfileRow(
+    profile: Profile,
+    path: Signal<String>,
+    status: Signal<Status>,
+    revision: Signal<i32>,
+) -> Element {
+    let mut cache = consume_context::<Signal<ModelCache>>();
+    let pins = consume_context::<Signal<Pins>>();
+
+    let models = profile
+        .models
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let apply = {
+        let profile = profile.clone();
+        move |_| {
+            let profile = profile.clone();
+            spawn(async move {
+                // Models of the profile which were not looked at yet have to be fetched first
+                let models = profile
+                    .models
+                    .iter()
+                    .map(|(name, selection)| ModelData {
+                        repo: selection.repo.clone(),
+                        name: name.clone(),
+                        git_ref: pins.read().model.clone(),
+                    })
+                    .collect();
+                if let Some(error) = fetch_missing(cache, models).await.into_iter().next() {
+                    status.set(Some(Err(error)));
+                    return;
+                }
+
+                cache.write().apply_profile(&profile);
+                status.set(Some(Ok(format!("Applied {}", profile.name))));
+            });
+        }
+    };
+
+    let export = {
+        let profile = profile.clone();
+        move |_| {
+            let path = PathBuf::from(path());
+            status.set(Some(match profile.save_to(&path) {
+                Ok(()) => Ok(format!("Exported {} to {}", profile.name, path.display())),
+                Err(err) => Err(err.to_string()),
+            }));
+        }
+    };
+
+    let delete = {
+        let name = profile.name.clone();
+        move |_| {
+            if let Err(err) = Profile::delete_saved(&name) {
+                status.set(Some(Err(err.to_string())));
+            }
+            revision += 1;
+        }
+    };
+
+    rsx!(tr {
+        td {
+            class: "pr-4 py-1 font-medium text-slate-900",
+            "{profile.name}"
+        },
+        td {
+            class: "pr-4 py-1 text-xs text-slate-500",
+            "{models}"
+        },
+        td {
+            class: "py-1 flex flex-row gap-2",
+            button { class: BUTTON_STYLE, onclick: apply, "Apply" },
+            button { class: BUTTON_STYLE, onclick: export, "Export" },
+            button { class: BUTTON_STYLE, onclick: delete, "Delete" },
+        }
+    })
+}
@@ -0,0 +1,241 @@
+use std::path::PathBuf;
+
+use dioxus::prelude::*;
+
+use crate::{
+    codegen::Language,
+    components::container::Container,
+    profile::Profile,
+    workspace::{Workspace, WorkspaceEntry},
+    ModelData,
+};
+
+const INPUT_STYLE: &str = "h-8 w-64 rounded-md border px-2 text-sm";
+const BUTTON_STYLE: &str = "px-3 rounded-md text-sm border hover:bg-gray-100";
+
+/// The members of the open workspace, shown above the full catalog.
+#[component]
+pub fn WorkspaceMembers(model_data: Signal<Option<ModelData>>) -> Element {
+    let workspace = consume_context::<Signal<Workspace>>();
+    let entries = workspace.read().entries.clone();
+
+    if entries.is_empty() {
+        return None;
+    }
+
+    let is_selected = |entry: &WorkspaceEntry| match model_data.read().as_ref() {
+        Some(model_data) => model_data.name == entry.model,
+        None => false,
+    };
+
+    rsx!(div {
+        class: "w-full border rounded-lg shadow-sm px-3 py-1",
+        h2 {
+            class: "my-1 text-base font-medium tracking-tight text-slate-900",
+            "Workspace"
+        },
+        ul {
+            for entry in entries {
+                li {
+                    class: if is_selected(&entry) {
+                        "p-1 m-1 text-sm text-slate-500 rounded-md bg-green-100 hover:cursor-pointer"
+                    } else {
+                        "p-1 m-1 text-sm text-slate-500 rounded-md hover:bg-gray-100 hover:cursor-pointer"
+                    },
+                    onclick: {
+                        let model = entry.model_data();
+                        move |_| model_data.set(Some(model.clone()))
+                    },
+                    "{entry.model} ",
+                    span {
+                        class: "text-xs text-slate-300",
+                        "@ {entry.git_ref}"
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[component]
+pub fn WorkspaceView() -> Element {
+    let mut workspace = consume_context::<Signal<Workspace>>();
+    let mut path = use_signal(|| {
+        let workspace = workspace.read();
+        workspace
+            .path
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default()
+    });
+    let mut output_dir = use_signal(|| String::from("generated"));
+    let mut language = use_signal(|| Language::JavaScript);
+    let mut status = use_signal(|| None);
+
+    let entries = workspace.read().entries.clone();
+    let profiles: Vec<_> = Profile::list_saved().into_iter().map(|p| p.name).collect();
+
+    let open = move |_| {
+        status.set(Some(match Workspace::load(&PathBuf::from(path())) {
+            Ok(opened) => {
+                let message = format!("Opened {} entries", opened.entries.len());
+                workspace.set(opened);
+                Ok(message)
+            }
+            Err(err) => Err(err.to_string()),
+        }));
+    };
+
+    let save = move |_| {
+        let path = PathBuf::from(path());
+        status.set(Some(match workspace.write().save(&path) {
+            Ok(()) => Ok(format!("Saved to {}", path.display())),
+            Err(err) => Err(err.to_string()),
+        }));
+    };
+
+    let generate_all = move |_| {
+        let workspace = workspace.read().clone();
+        let output_dir = PathBuf::from(output_dir());
+        let language = language();
+        status.set(Some(Ok(String::from("Generating..."))));
+        spawn(async move {
+            status.set(Some(
+                match workspace.generate_all(&output_dir, language).await {
+                    Ok((written, errors)) => with_errors(
+                        format!("Wrote {} files to {}", written.len(), output_dir.display()),
+                        errors,
+                    ),
+                    Err(err) => Err(err.to_string()),
+                },
+            ));
+        });
+    };
+
+    let export_openapi = move |_| {
+        let workspace = workspace.read().clone();
+        let output_dir = PathBuf::from(output_dir());
+        status.set(Some(Ok(String::from("Exporting..."))));
+        spawn(async move {
+            status.set(Some(match workspace.export_openapi(&output_dir).await {
+                Ok((path, errors)) => with_errors(format!("Wrote {}", path.display()), errors),
+                Err(err) => Err(err.to_string()),
+            }));
+        });
+    };
+
+    rsx!(Container {
+        h1 {
+            class: "font-bold text-slate-950 text-lg",
+            "Workspace"
+        },
+        div {
+            class: "flex flex-row gap-2",
+            input {
+                class: INPUT_STYLE,
+                value: "{path}",
+                placeholder: "Path of the project file",
+                spellcheck: false,
+                oninput: move |event| path.set(event.value())
+            },
+            button { class: BUTTON_STYLE, onclick: open, "Open" },
+            button { class: BUTTON_STYLE, onclick: save, "Save" },
+        },
+        if entries.is_empty() {
+            p {
+                class: "text-sm text-slate-500",
+                "Add models with the workspace button of a model."
+            }
+        },
+        table {
+            class: "text-sm",
+            for entry in entries {
+                tr {
+                    td {
+                        class: "pr-4 py-1 font-medium text-slate-900",
+                        "{entry.model}"
+                    },
+                    td {
+                        class: "pr-4 py-1 text-xs text-slate-500",
+                        "{entry.repo} @ {entry.git_ref}"
+                    },
+                    td {
+                        class: "pr-4 py-1",
+                        select {
+                            class: "border text-xs text-slate-500",
+                            onchange: {
+                                let entry = entry.clone();
+                                move |event: FormEvent| {
+                                    let value = event.value();
+                                    workspace.write().add(WorkspaceEntry {
+                                        profile: (!value.is_empty()).then_some(value),
+                                        ..entry.clone()
+                                    });
+                                }
+                            },
+                            option { value: "", "required only" },
+                            for profile in profiles.iter() {
+                                option {
+                                    value: "{profile}",
+                                    selected: entry.profile.as_ref() == Some(profile),
+                                    "{profile}"
+                                }
+                            }
+                        }
+                    },
+                    td {
+                        class: "py-1",
+                        button {
+                            class: "text-xs text-slate-400 hover:text-slate-900",
+                            onclick: {
+                                let model = entry.model.clone();
+                                move |_| workspace.write().remove(&model)
+                            },
+                            "✕"
+                        }
+                    }
+                }
+            }
+        },
+        div {
+            class: "flex flex-row gap-2",
+            input {
+                class: INPUT_STYLE,
+                value: "{output_dir}",
+                placeholder: "Output directory",
+                spellcheck: false,
+                oninput: move |event| output_dir.set(event.value())
+            },
+            select {
+                class: "border text-sm text-slate-500",
+                onchange: move |event| {
+                    if let Some(l) = Language::from_name(&event.value()) {
+                        language.set(l);
+                    }
+                },
+                for l in Language::array() {
+                    option {
+                        value: "{l.str()}",
+                        selected: language() == l,
+                        "{l.str()}"
+                    }
+                }
+            },
+            button { class: BUTTON_STYLE, onclick: generate_all, "Generate all" },
+            button { class: BUTTON_STYLE, onclick: export_openapi, "Export OpenAPI components" },
+        },
+        match status() {
+            Some(Ok(message)) => rsx!(p { class: "text-xs text-green-600", "{message}" }),
+            Some(Err(error)) => rsx!(p { class: "text-xs text-red-500", "{error}" }),
+            None => None,
+        },
+    })
+}
+
+/// The message of a run, which is an error when some entries failed.
+fn with_errors(message: String, errors: Vec<String>) -> Result<String, String> {
+    if errors.is_empty() {
+        return Ok(message);
+    }
+    Err(format!("{message}, failed: {}", errors.join("; ")))
+}
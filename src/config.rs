@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+
+/// Directory for files which outlive a session, e.g. `~/.config/sdm_browser`.
+/// Falls back to the working directory if no home directory is known.
+pub fn config_dir() -> PathBuf {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    base.join(env!("CARGO_PKG_NAME"))
+}
@@ -3,29 +3,56 @@
 use dioxus::{desktop::Config, prelude::*};
 
 use crate::{
+    broker::BrokerSettings,
     cache::ModelCache,
-    components::{list::FilteredList, model::Model},
-    smartdata::models::ModelList,
+    components::{
+        broker::{BrokerView, ExchangeLog},
+        builder::KeptEntity,
+        compare::Compare,
+        conformance::Conformance,
+        diff::Diff,
+        graph::RelationshipGraph,
+        inference::Inference,
+        list::FilteredList,
+        model::Model,
+        nav::{Nav, View},
+        pins::PinSettings,
+        profiles::Profiles,
+        workspace::WorkspaceView,
+    },
+    smartdata::{models::ModelList, refs::DEFAULT_REF},
+    workspace::Workspace,
 };
 
+mod broker;
 mod cache;
+mod cli;
 mod codegen;
 mod components;
+mod config;
+mod ngsi;
+mod profile;
 mod smartdata;
+mod workspace;
 
 const TAILWIND_LINK: &str = r#"<link rel="stylesheet" href="public/tailwind.css">"#;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     let config = Config::new().with_custom_head(TAILWIND_LINK.to_string());
 
     LaunchBuilder::desktop().with_cfg(config).launch(App);
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct ModelData {
     repo: String,
     name: String,
-    url: String,
+    git_ref: String,
 }
 
 impl ModelData {
@@ -37,6 +64,22 @@ impl ModelData {
     }
 }
 
+/// The git refs the official list and newly selected models are fetched at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pins {
+    pub list: String,
+    pub model: String,
+}
+
+impl Default for Pins {
+    fn default() -> Self {
+        Pins {
+            list: DEFAULT_REF.to_string(),
+            model: DEFAULT_REF.to_string(),
+        }
+    }
+}
+
 #[component]
 fn ShowError(error: String) -> Element {
     rsx!(p {
@@ -49,16 +92,27 @@ fn App() -> Element {
     // SIGNALS
     let mut cache = use_context_provider(|| Signal::new(ModelCache::new()));
     let model_data = use_signal(|| None);
+    let view = use_signal(|| View::Model);
+    use_context_provider(|| Signal::new(Vec::<String>::new()));
+    let pins = use_context_provider(|| Signal::new(Pins::default()));
+    use_context_provider(|| Signal::new(Workspace::default()));
+    use_context_provider(|| Signal::new(Vec::<KeptEntity>::new()));
+    use_context_provider(|| Signal::new(BrokerSettings::load_saved().unwrap_or_default()));
+    use_context_provider(|| Signal::new(ExchangeLog::new()));
 
     // RESOURCES and RENDERED RESOURCE
-    let model_list = use_resource(|| async move { ModelList::fetch().await });
+    let model_list = use_resource(move || async move { ModelList::fetch(&pins.read().list).await });
     let rendered_model_list = match &*model_list.read() {
         Some(Ok(list)) => rsx!(FilteredList {
             list: list.to_owned(),
             model_data,
         }),
-        Some(Err(err)) => rsx!(ShowError {
-            error: err.to_string(),
+        Some(Err(err)) => rsx!(div {
+            class: "flex flex-col",
+            ShowError {
+                error: err.to_string(),
+            },
+            PinSettings {}
         }),
         None => None,
     };
@@ -72,7 +126,8 @@ fn App() -> Element {
     let rendered_selected_model = match &*selected_model.read() {
         // The nesting is kinda ugly, but the logic in the resource is better this way
         Some(Some(Ok(model))) => rsx!(Model {
-            model: model.clone()
+            model: model.clone(),
+            model_data,
         }),
         Some(Some(Err(err))) => rsx!(ShowError {
             error: err.to_string(),
@@ -81,9 +136,46 @@ fn App() -> Element {
         None => None,
     };
 
+    let rendered_view = match view() {
+        View::Model => rendered_selected_model,
+        View::Compare => rsx!(Compare { view }),
+        View::Diff => rsx!(Diff { model_data }),
+        View::Profiles => rsx!(Profiles {}),
+        View::Workspace => rsx!(WorkspaceView {}),
+        View::Broker => rsx!(BrokerView {}),
+        View::Graph => match &*model_list.read() {
+            Some(Ok(list)) => rsx!(RelationshipGraph {
+                list: list.to_owned(),
+                model_data,
+                view,
+            }),
+            _ => None,
+        },
+        View::Conformance => match &*model_list.read() {
+            Some(Ok(list)) => rsx!(Conformance {
+                list: list.to_owned(),
+                model_data,
+                view,
+            }),
+            _ => None,
+        },
+        View::Inference => match &*model_list.read() {
+            Some(Ok(list)) => rsx!(Inference {
+                list: list.to_owned(),
+                model_data,
+                view,
+            }),
+            _ => None,
+        },
+    };
+
     rsx!(div {
         class: "flex flex-row",
         {rendered_model_list},
-        {rendered_selected_model},
+        div {
+            class: "w-full flex flex-col",
+            Nav { view },
+            {rendered_view},
+        }
     })
 }
@@ -0,0 +1,370 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    codegen::types::FieldType,
+    ngsi::{document, Format},
+    smartdata::models::{GeoProperty, ParsedModel, Property},
+};
+
+/// The inputs of the entity builder form, as the user typed them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityForm {
+    pub id: String,
+    /// Raw inputs by property name, objects and arrays are typed as json.
+    pub inputs: BTreeMap<String, String>,
+    /// Rows of the coordinate editor by property name, each `[longitude, latitude]`.
+    pub positions: BTreeMap<String, Vec<[String; 2]>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    /// The property the error belongs to, `id` for the entity id.
+    pub property: String,
+    pub message: String,
+}
+
+impl EntityForm {
+    pub fn new(model_name: &str) -> Self {
+        EntityForm {
+            id: format!("urn:ngsi-ld:{model_name}:"),
+            ..Default::default()
+        }
+    }
+
+    pub fn add_position(&mut self, name: &str) {
+        self.positions
+            .entry(name.to_string())
+            .or_default()
+            .push(Default::default());
+    }
+
+    pub fn remove_position(&mut self, name: &str, row: usize) {
+        if let Some(rows) = self.positions.get_mut(name) {
+            if row < rows.len() {
+                rows.remove(row);
+            }
+        }
+    }
+
+    /// Sets the longitude (`axis` 0) or the latitude (`axis` 1) of a row.
+    pub fn set_coordinate(&mut self, name: &str, row: usize, axis: usize, value: String) {
+        let position = self
+            .positions
+            .get_mut(name)
+            .and_then(|rows| rows.get_mut(row));
+        if let Some(coordinate) = position.and_then(|position| position.get_mut(axis)) {
+            *coordinate = value;
+        }
+    }
+
+    /// Parses the inputs of the checked properties of `model` into key-values attributes and
+    /// collects what is missing or invalid. Empty inputs are left out of the entity.
+    pub fn attributes(&self, model: &ParsedModel) -> (Map<String, Value>, Vec<FieldError>) {
+        let mut attributes = Map::new();
+        let mut errors = vec![];
+        let mut fail = |property: &str, message: String| {
+            errors.push(FieldError {
+                property: property.to_string(),
+                message,
+            })
+        };
+
+        if !is_uri(&self.id) || self.id.ends_with(':') {
+            fail("id", String::from("must be a complete uri"));
+        }
+
+        for property in model.properties.iter().filter(|p| p.checked) {
+            let field = FieldType::from_property(property);
+            let parsed = match field {
+                FieldType::Geo(ref variant) => {
+                    let positions = self.positions.get(&property.name);
+                    match positions.filter(|rows| !rows.is_empty()) {
+                        Some(rows) => geometry(variant.as_deref(), rows).map(Some),
+                        None => Ok(None),
+                    }
+                }
+                _ => {
+                    let input = self.inputs.get(&property.name).map(|i| i.trim());
+                    match input.filter(|input| !input.is_empty()) {
+                        Some(input) => parse(property, &field, input).map(Some),
+                        None => Ok(None),
+                    }
+                }
+            };
+
+            match parsed {
+                Ok(Some(value)) => {
+                    attributes.insert(property.name.clone(), value);
+                }
+                Ok(None) if property.required => {
+                    fail(&property.name, String::from("is required"));
+                }
+                Ok(None) => {}
+                Err(message) => fail(&property.name, message),
+            }
+        }
+        (attributes, errors)
+    }
+
+    /// The entity in the representation of `format`, with the errors of the inputs.
+    pub fn document(&self, model: &ParsedModel, format: Format) -> (Value, Vec<FieldError>) {
+        let (attributes, errors) = self.attributes(model);
+        (document(model, &self.id, &attributes, format), errors)
+    }
+}
+
+/// Parses the input of a property with a single value input.
+fn parse(property: &Property, field: &FieldType, input: &str) -> Result<Value, String> {
+    let value = match field {
+        FieldType::String => json!(input),
+        FieldType::Integer => json!(input
+            .parse::<i64>()
+            .map_err(|_| String::from("must be a whole number"))?),
+        FieldType::Number => json!(input
+            .parse::<f64>()
+            .map_err(|_| String::from("must be a number"))?),
+        FieldType::Boolean => json!(input
+            .parse::<bool>()
+            .map_err(|_| String::from("must be true or false"))?),
+        FieldType::DateTime => {
+            json!(date_time(input).ok_or_else(|| String::from("must be a date and time"))?)
+        }
+        FieldType::Date if is_date(input) => json!(input),
+        FieldType::Date => return Err(String::from("must be a date like 2024-01-31")),
+        FieldType::Time if is_time(input) => json!(with_seconds(input)),
+        FieldType::Time => return Err(String::from("must be a time like 13:30")),
+        FieldType::Uri if is_uri(input) => json!(input),
+        FieldType::Uri => return Err(String::from("must be an uri")),
+        FieldType::Enum(values) if values.iter().any(|v| v == input) => json!(input),
+        FieldType::Enum(values) => {
+            return Err(format!("must be one of {}", values.join(", ")));
+        }
+        FieldType::Relationship(_) if is_uri(input) => json!(input),
+        FieldType::Relationship(_) => return Err(String::from("must be the uri of an entity")),
+        FieldType::Object | FieldType::Array(_) | FieldType::Geo(_) => {
+            let value: Value =
+                serde_json::from_str(input).map_err(|err| format!("is no valid json: {err}"))?;
+            match (field, &value) {
+                (FieldType::Object | FieldType::Geo(_), Value::Object(_))
+                | (FieldType::Array(_), Value::Array(_)) => value,
+                (FieldType::Array(_), _) => return Err(String::from("must be a json array")),
+                _ => return Err(String::from("must be a json object")),
+            }
+        }
+    };
+
+    match &value {
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if property.minimum.is_some_and(|minimum| number < minimum) {
+                return Err(format!("must be at least {}", property.minimum.unwrap()));
+            }
+            if property.maximum.is_some_and(|maximum| number > maximum) {
+                return Err(format!("must be at most {}", property.maximum.unwrap()));
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if property.min_length.is_some_and(|min| length < min) {
+                return Err(format!(
+                    "must have at least {} characters",
+                    property.min_length.unwrap()
+                ));
+            }
+            if property.max_length.is_some_and(|max| length > max) {
+                return Err(format!(
+                    "must have at most {} characters",
+                    property.max_length.unwrap()
+                ));
+            }
+            // Patterns the regex crate cannot compile, like ones with lookarounds, are skipped
+            if let Some(pattern) = &property.pattern {
+                if Regex::new(pattern).is_ok_and(|regex| !regex.is_match(text)) {
+                    return Err(format!("must match the pattern {pattern}"));
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(value)
+}
+
+/// A GeoJSON geometry of the rows of the coordinate editor. The editor has a single list of
+/// positions, so multi geometries get a single part and polygons a single closed ring.
+fn geometry(variant: Option<&str>, rows: &[[String; 2]]) -> Result<Value, String> {
+    let mut positions = vec![];
+    for (i, [longitude, latitude]) in rows.iter().enumerate() {
+        let longitude: f64 = longitude.trim().parse().unwrap_or(f64::NAN);
+        let latitude: f64 = latitude.trim().parse().unwrap_or(f64::NAN);
+        if !(-180.0..=180.0).contains(&longitude) || !(-90.0..=90.0).contains(&latitude) {
+            return Err(format!(
+                "position {} is no valid longitude and latitude",
+                i + 1
+            ));
+        }
+        positions.push(json!([longitude, latitude]));
+    }
+
+    let variant = variant.unwrap_or(GeoProperty::Point.str());
+    let minimum = match variant {
+        "Point" | "MultiPoint" => 1,
+        "LineString" | "MultiLineString" => 2,
+        _ => 3,
+    };
+    if positions.len() < minimum {
+        return Err(format!("a {variant} needs at least {minimum} positions"));
+    }
+    if minimum == 3 && positions.first() != positions.last() {
+        positions.push(positions[0].clone());
+    }
+
+    let coordinates = match variant {
+        "Point" => positions[0].clone(),
+        "LineString" | "MultiPoint" => json!(positions),
+        "Polygon" | "MultiLineString" => json!([positions]),
+        _ => json!([[positions]]),
+    };
+    Ok(json!({"type": variant, "coordinates": coordinates}))
+}
+
+/// Whether `text` starts with an uri scheme like `urn:` or `https:`.
+fn is_uri(text: &str) -> bool {
+    match text.split_once(':') {
+        Some((scheme, rest)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+                && !rest.is_empty()
+                && !text.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+fn is_date(text: &str) -> bool {
+    matches_digits(text, "dddd-dd-dd")
+}
+
+/// `HH:MM` as date inputs give it, or with seconds.
+fn is_time(text: &str) -> bool {
+    matches_digits(text, "dd:dd") || matches_digits(text, "dd:dd:dd")
+}
+
+fn with_seconds(time: &str) -> String {
+    if time.len() == 5 {
+        format!("{time}:00")
+    } else {
+        time.to_string()
+    }
+}
+
+/// A date-time in UTC, local date-time inputs without a zone are taken as UTC.
+fn date_time(text: &str) -> Option<String> {
+    let (date, time) = text.split_once('T').or_else(|| text.split_once(' '))?;
+    if !is_date(date) {
+        return None;
+    }
+    let zone_start = time.find(['Z', '+', '-']).unwrap_or(time.len());
+    let (clock, zone) = time.split_at(zone_start);
+    let clock = clock.split('.').next().unwrap_or_default();
+    if !is_time(clock) {
+        return None;
+    }
+    let zone = match zone {
+        "" => "Z",
+        "Z" => zone,
+        _ if matches_digits(&zone[1..], "dd:dd") => zone,
+        _ => return None,
+    };
+    Some(format!("{date}T{}{zone}", with_seconds(clock)))
+}
+
+/// Whether `text` matches `pattern`, in which `d` stands for a digit.
+fn matches_digits(text: &str, pattern: &str) -> bool {
+    text.len() == pattern.len()
+        && text.chars().zip(pattern.chars()).all(|(c, p)| match p {
+            'd' => c.is_ascii_digit(),
+            _ => c == p,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        ngsi::Format,
+        smartdata::models::{ParsedModel, Property},
+    };
+
+    use super::EntityForm;
+
+    #[test]
+    fn build_entity() {
+        let model = ParsedModel {
+            name: "Device".to_string(),
+            properties: vec![
+                Property::from_yaml("batteryLevel", "{type: number, maximum: 1}"),
+                Property::from_yaml("dateCreated", "{type: string, format: date-time}"),
+                Property::from_yaml("location", "{}"),
+                Property::from_yaml("refOwner", "{type: string, x-ngsi: {type: Relationship}}"),
+                Property::from_yaml("serialNumber", r"{type: string, pattern: '^[A-Z]{2}-\d+$'}"),
+                Property {
+                    required: true,
+                    ..Property::from_yaml("status", "{type: string, enum: [ok, broken]}")
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut form = EntityForm::new("Device");
+        form.id.push('1');
+        form.inputs = [
+            ("batteryLevel", "0.5"),
+            ("dateCreated", "2024-01-31T13:30"),
+            ("refOwner", "urn:ngsi-ld:Owner:7"),
+        ]
+        .into_iter()
+        .map(|(name, input)| (name.to_string(), input.to_string()))
+        .collect();
+        form.positions.insert(
+            "location".to_string(),
+            vec![["8.5".to_string(), "47.3".to_string()]],
+        );
+
+        let (entity, errors) = form.document(&model, Format::KeyValues);
+        assert_eq!(entity["batteryLevel"], json!(0.5));
+        assert_eq!(entity["dateCreated"], json!("2024-01-31T13:30:00Z"));
+        assert_eq!(
+            entity["location"],
+            json!({"type": "Point", "coordinates": [8.5, 47.3]})
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            (errors[0].property.as_str(), errors[0].message.as_str()),
+            ("status", "is required")
+        );
+
+        form.inputs
+            .insert("batteryLevel".to_string(), "2".to_string());
+        form.inputs
+            .insert("refOwner".to_string(), "Owner 7".to_string());
+        form.inputs
+            .insert("serialNumber".to_string(), "ab-1".to_string());
+        form.inputs.insert("status".to_string(), "ok".to_string());
+        let (_, errors) = form.attributes(&model);
+        let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "must be at most 1",
+                "must be the uri of an entity",
+                r"must match the pattern ^[A-Z]{2}-\d+$",
+            ]
+        );
+    }
+}
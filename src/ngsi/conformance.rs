@@ -0,0 +1,273 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    codegen::types::FieldType,
+    ngsi::{is_kind, ENTITY_MEMBERS},
+    smartdata::models::ParsedModel,
+};
+
+/// Attributes the NGSI-LD core context defines for every entity.
+const CORE_ATTRIBUTES: [&str; 3] = ["location", "observationSpace", "operationSpace"];
+
+/// A way in which an entity differs from its model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "issue", rename_all = "camelCase")]
+pub enum Issue {
+    MissingRequired {
+        attribute: String,
+    },
+    Unknown {
+        attribute: String,
+    },
+    WrongKind {
+        attribute: String,
+        expected: &'static str,
+        found: String,
+    },
+}
+
+impl Issue {
+    pub fn to_text(&self) -> String {
+        match self {
+            Issue::MissingRequired { attribute } => format!("{attribute} is required but missing"),
+            Issue::Unknown { attribute } => format!("{attribute} is not defined by the model"),
+            Issue::WrongKind {
+                attribute,
+                expected,
+                found,
+            } => format!("{attribute} must be a {expected}, found {found}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntityReport {
+    pub id: String,
+    pub issues: Vec<Issue>,
+}
+
+/// The entities of a type in the broker, checked against the model of the same name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TypeReport {
+    /// The type as the broker names it, possibly expanded to an uri.
+    #[serde(rename = "type")]
+    pub typ: String,
+    /// The repository of the matching model.
+    pub repo: Option<String>,
+    /// False if no model was found or it failed to load, then no entity counts as conforming.
+    pub checked: bool,
+    pub entities: Vec<EntityReport>,
+}
+
+impl TypeReport {
+    pub fn new(typ: &str, model: Option<&ParsedModel>, entities: &[Value]) -> Self {
+        TypeReport {
+            typ: typ.to_string(),
+            repo: model.map(|model| model.repo.clone()),
+            checked: model.is_some(),
+            entities: entities
+                .iter()
+                .map(|entity| EntityReport {
+                    id: entity["id"].as_str().unwrap_or_default().to_string(),
+                    issues: model.map(|model| check(model, entity)).unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn conforming(&self) -> usize {
+        if !self.checked {
+            return 0;
+        }
+        self.entities.iter().filter(|e| e.issues.is_empty()).count()
+    }
+}
+
+/// How well the entities of a running broker follow the smart data models.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConformanceReport {
+    pub broker: String,
+    pub git_ref: String,
+    /// Only if every type was checked and all its entities conform.
+    pub conforming: bool,
+    /// The types which could not be checked against a model.
+    pub unchecked: Vec<String>,
+    pub types: Vec<TypeReport>,
+}
+
+impl ConformanceReport {
+    pub fn new(broker: &str, git_ref: &str, types: Vec<TypeReport>) -> Self {
+        let unchecked: Vec<_> = types
+            .iter()
+            .filter(|t| !t.checked)
+            .map(|t| t.typ.clone())
+            .collect();
+        ConformanceReport {
+            broker: broker.to_string(),
+            git_ref: git_ref.to_string(),
+            conforming: unchecked.is_empty()
+                && types.iter().all(|t| t.conforming() == t.entities.len()),
+            unchecked,
+            types,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report is always serializable")
+    }
+}
+
+/// The last segment of an expanded type or attribute name, brokers expand the names which
+/// the context of a request does not define, e.g. `https://smartdatamodels.org/dataModel.Device/Device`.
+pub fn short_name(name: &str) -> &str {
+    name.rsplit(['/', '#']).next().unwrap_or(name)
+}
+
+/// Checks a normalized entity against `model`.
+pub fn check(model: &ParsedModel, entity: &Value) -> Vec<Issue> {
+    let mut issues = vec![];
+    let Some(members) = entity.as_object() else {
+        return issues;
+    };
+    let attributes: Vec<_> = members
+        .iter()
+        .filter(|(name, _)| !ENTITY_MEMBERS.contains(&name.as_str()))
+        .map(|(name, attribute)| (short_name(name), attribute))
+        .collect();
+
+    for property in model.properties.iter().filter(|p| p.required) {
+        let name = property.name.as_str();
+        if !ENTITY_MEMBERS.contains(&name) && !attributes.iter().any(|(n, _)| *n == name) {
+            issues.push(Issue::MissingRequired {
+                attribute: name.to_string(),
+            });
+        }
+    }
+
+    for (name, attribute) in attributes {
+        let Some(property) = model.property(name) else {
+            if !CORE_ATTRIBUTES.contains(&name) {
+                issues.push(Issue::Unknown {
+                    attribute: name.to_string(),
+                });
+            }
+            continue;
+        };
+
+        let expected = FieldType::from_property(property).ngsi_kind();
+        // An attribute with several instances is an array of them, each with its datasetId
+        let instances = match attribute {
+            Value::Array(instances) => instances.iter().collect(),
+            _ => vec![attribute],
+        };
+        for instance in instances {
+            let found = instance["type"].as_str().unwrap_or("a plain value");
+            if !is_kind(found, expected) {
+                issues.push(Issue::WrongKind {
+                    attribute: name.to_string(),
+                    expected,
+                    found: found.to_string(),
+                });
+                break;
+            }
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::{check, short_name, ConformanceReport, Issue, TypeReport};
+
+    #[test]
+    fn check_entities() {
+        let model = ParsedModel {
+            name: "Device".to_string(),
+            repo: "Device".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("id", "{type: string}")
+                },
+                Property::from_yaml("batteryLevel", "{type: number}"),
+                Property {
+                    required: true,
+                    ..Property::from_yaml(
+                        "controlledAsset",
+                        "{type: string, x-ngsi: {type: Relationship}}",
+                    )
+                },
+                Property::from_yaml("location", "{x-ngsi: {type: GeoProperty}}"),
+                Property::from_yaml("name", "{type: string}"),
+            ],
+            ..Default::default()
+        };
+
+        let conforming = json!({
+            "id": "urn:ngsi-ld:Device:1",
+            "type": "https://smartdatamodels.org/dataModel.Device/Device",
+            "https://smartdatamodels.org/dataModel.Device/controlledAsset": {
+                "type": "Relationship",
+                "object": "urn:ngsi-ld:Building:1",
+            },
+            "name": {"type": "LanguageProperty", "languageMap": {"en": "Meter"}},
+            "createdAt": "2024-01-01T00:00:00Z",
+        });
+        assert_eq!(check(&model, &conforming), vec![]);
+
+        let broken = json!({
+            "id": "urn:ngsi-ld:Device:2",
+            "type": "Device",
+            "batteryLevel": [
+                {"type": "Property", "value": 0.5, "datasetId": "urn:a"},
+                {"type": "Relationship", "object": "urn:b", "datasetId": "urn:b"},
+            ],
+            "location": {"type": "Property", "value": {"type": "Point", "coordinates": [8, 47]}},
+            "colour": {"type": "Property", "value": "red"},
+        });
+        let issues: Vec<_> = check(&model, &broken).iter().map(Issue::to_text).collect();
+        assert_eq!(
+            issues,
+            [
+                "controlledAsset is required but missing",
+                "batteryLevel must be a Property, found Relationship",
+                "location must be a GeoProperty, found Property",
+                "colour is not defined by the model",
+            ]
+        );
+
+        assert_eq!(short_name(conforming["type"].as_str().unwrap()), "Device");
+        let report = ConformanceReport::new(
+            "http://localhost:1026",
+            "master",
+            vec![TypeReport::new(
+                "Device",
+                Some(&model),
+                &[conforming, broken],
+            )],
+        );
+        assert_eq!(report.types[0].conforming(), 1);
+        assert!(!report.conforming);
+        assert!(report.to_json().contains("\"issue\": \"wrongKind\""));
+
+        let valid = json!({"id": "urn:ngsi-ld:Tank:1", "type": "Tank"});
+        let report = ConformanceReport::new(
+            "http://localhost:1026",
+            "master",
+            vec![TypeReport::new("Tank", None, &[valid])],
+        );
+        assert_eq!(report.types[0].conforming(), 0);
+        assert_eq!(report.unchecked, ["Tank"]);
+        assert!(
+            !report.conforming,
+            "types without a model are not conforming"
+        );
+        assert!(report.to_json().contains("\"checked\": false"));
+    }
+}
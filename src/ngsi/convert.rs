@@ -0,0 +1,496 @@
+use serde_json::{json, Map, Value};
+
+use crate::{
+    codegen::types::FieldType,
+    ngsi::{conformance::short_name, context, is_kind, Format, CORE_CONTEXT, ENTITY_MEMBERS},
+    smartdata::models::ParsedModel,
+};
+
+/// The kinds of NGSI-LD attributes, besides `Relationship` which NGSI-v2 has as well.
+const LD_PROPERTY_KINDS: [&str; 5] = [
+    "Property",
+    "GeoProperty",
+    "LanguageProperty",
+    "VocabProperty",
+    "ListProperty",
+];
+
+/// The members holding the value of a normalized NGSI-LD attribute.
+const VALUE_MEMBERS: [&str; 5] = ["value", "object", "languageMap", "vocab", "valueList"];
+
+/// An attribute apart from its representation.
+struct Attribute {
+    value: Value,
+    /// The NGSI-LD kind or the NGSI-v2 type of a normalized attribute.
+    kind: Option<String>,
+    unit_code: Option<Value>,
+    observed_at: Option<Value>,
+    /// The sub-properties and sub-relationships of NGSI-LD or the other metadata of NGSI-v2,
+    /// like `providedBy` or `accuracy`.
+    sub_attributes: Vec<(String, Attribute)>,
+    /// Other members of an NGSI-LD attribute, like `datasetId`.
+    members: Map<String, Value>,
+}
+
+/// Guesses the representation of an entity from its first wrapped attribute.
+pub fn detect(entity: &Value) -> Format {
+    let Some(members) = entity.as_object() else {
+        return Format::KeyValues;
+    };
+    for (name, attribute) in members {
+        if ENTITY_MEMBERS.contains(&name.as_str()) {
+            continue;
+        }
+        let attribute = match attribute {
+            Value::Array(instances) => instances.first().unwrap_or(attribute),
+            _ => attribute,
+        };
+        let Some(kind) = attribute.get("type").and_then(Value::as_str) else {
+            continue;
+        };
+        if attribute.get("object").is_some()
+            || attribute.get("languageMap").is_some()
+            || LD_PROPERTY_KINDS.contains(&kind)
+        {
+            return Format::Normalized;
+        }
+        if attribute.get("value").is_some() {
+            return Format::V2Normalized;
+        }
+    }
+    Format::KeyValues
+}
+
+/// Converts `entity` from the representation `from` to `to`. The kinds of the properties of
+/// `model` decide how attributes are wrapped, attributes it does not define keep the kind they
+/// had or get one from their value. Returns the entity with notes on what could not be kept.
+pub fn convert(
+    model: Option<&ParsedModel>,
+    entity: &Value,
+    from: Format,
+    to: Format,
+) -> Result<(Value, Vec<String>), String> {
+    let members = entity
+        .as_object()
+        .ok_or_else(|| String::from("an entity must be a json object"))?;
+    let mut notes = vec![];
+    let typ = members
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if let Some(model) = model.filter(|model| short_name(typ) != model.name) {
+        notes.push(format!(
+            "the entity is a {typ}, its attributes are wrapped like the ones of {}",
+            model.name
+        ));
+    }
+
+    let mut converted = Map::new();
+    for (name, member) in members {
+        if name == "@context" {
+            continue;
+        }
+        if ENTITY_MEMBERS.contains(&name.as_str()) {
+            // NGSI-v2 has no scopes and the broker sets the timestamps
+            if name == "id" || name == "type" || to != Format::V2Normalized {
+                converted.insert(name.clone(), member.clone());
+            }
+            continue;
+        }
+
+        let attribute = read(name, member, from, &mut notes);
+        let field = model
+            .and_then(|model| model.property(short_name(name)))
+            .map(FieldType::from_property);
+        let written = write(name, field.as_ref(), attribute, to, &mut notes);
+        converted.insert(name.clone(), written);
+    }
+
+    if to != Format::V2Normalized {
+        let context = match (members.get("@context"), model) {
+            (Some(context), _) => context.clone(),
+            (None, Some(model)) => context(model),
+            (None, None) => json!(CORE_CONTEXT),
+        };
+        converted.insert("@context".to_string(), context);
+    }
+    Ok((Value::Object(converted), notes))
+}
+
+fn read(name: &str, member: &Value, from: Format, notes: &mut Vec<String>) -> Attribute {
+    let member = match member {
+        Value::Array(instances) if from == Format::Normalized && !instances.is_empty() => {
+            if instances.len() > 1 {
+                notes.push(format!("{name}: only the first of its instances is kept"));
+            }
+            &instances[0]
+        }
+        _ => member,
+    };
+    let mut attribute = Attribute {
+        value: member.clone(),
+        kind: None,
+        unit_code: None,
+        observed_at: None,
+        sub_attributes: vec![],
+        members: Map::new(),
+    };
+    let Some(wrapped) = member.as_object().filter(|_| from != Format::KeyValues) else {
+        return attribute;
+    };
+
+    let value = VALUE_MEMBERS
+        .iter()
+        .find_map(|key| wrapped.get(*key))
+        .cloned()
+        .unwrap_or(Value::Null);
+    // Normalized date-times are typed json-ld values
+    attribute.value = match value.get("@value") {
+        Some(plain_value) => plain_value.clone(),
+        None => value,
+    };
+    attribute.kind = wrapped
+        .get("type")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    match from {
+        Format::V2Normalized => {
+            let metadata = wrapped.get("metadata").and_then(Value::as_object);
+            for (key, metadatum) in metadata.into_iter().flatten() {
+                let value = || metadatum.get("value").cloned();
+                match key.as_str() {
+                    "unitCode" => attribute.unit_code = value(),
+                    "observedAt" => attribute.observed_at = value(),
+                    "TimeInstant" if !metadata.is_some_and(|m| m.contains_key("observedAt")) => {
+                        attribute.observed_at = value()
+                    }
+                    _ => {
+                        let sub_attribute = read(&format!("{name}.{key}"), metadatum, from, notes);
+                        attribute.sub_attributes.push((key.clone(), sub_attribute));
+                    }
+                }
+            }
+        }
+        _ => {
+            for (key, member) in wrapped {
+                match key.as_str() {
+                    "unitCode" => attribute.unit_code = Some(member.clone()),
+                    "observedAt" => attribute.observed_at = Some(member.clone()),
+                    key if key == "type" || VALUE_MEMBERS.contains(&key) => {}
+                    // Members with a kind are sub-attributes, the others are plain members
+                    _ if member.get("type").is_some() => {
+                        let sub_attribute = read(&format!("{name}.{key}"), member, from, notes);
+                        attribute.sub_attributes.push((key.clone(), sub_attribute));
+                    }
+                    _ => {
+                        attribute.members.insert(key.clone(), member.clone());
+                    }
+                }
+            }
+        }
+    }
+    attribute
+}
+
+fn write(
+    name: &str,
+    field: Option<&FieldType>,
+    attribute: Attribute,
+    to: Format,
+    notes: &mut Vec<String>,
+) -> Value {
+    let kind = attribute.kind.as_deref();
+    match to {
+        Format::KeyValues => {
+            let mut dropped = vec![];
+            if attribute.unit_code.is_some() {
+                dropped.push("unitCode");
+            }
+            if attribute.observed_at.is_some() {
+                dropped.push("observedAt");
+            }
+            dropped.extend(attribute.sub_attributes.iter().map(|(key, _)| key.as_str()));
+            dropped.extend(attribute.members.keys().map(String::as_str));
+            if !dropped.is_empty() {
+                notes.push(format!(
+                    "{name}: {} dropped, key-values have no metadata",
+                    enumerate(&dropped)
+                ));
+            }
+            attribute.value
+        }
+        Format::Normalized => {
+            let kind = match (field, kind) {
+                // Language, vocabulary and list properties stay what they are
+                (Some(field), Some(kind))
+                    if LD_PROPERTY_KINDS.contains(&kind) && is_kind(kind, field.ngsi_kind()) =>
+                {
+                    kind
+                }
+                (Some(field), _) => field.ngsi_kind(),
+                (None, Some(kind))
+                    if kind == "Relationship" || LD_PROPERTY_KINDS.contains(&kind) =>
+                {
+                    kind
+                }
+                (None, Some("geo:json")) => "GeoProperty",
+                (None, _) => "Property",
+            };
+            let is_date_time = match field {
+                Some(field) => *field == FieldType::DateTime,
+                None => kind == "Property" && attribute.kind.as_deref() == Some("DateTime"),
+            };
+            let value_member = match kind {
+                "Relationship" => "object",
+                "LanguageProperty" => "languageMap",
+                "VocabProperty" => "vocab",
+                "ListProperty" => "valueList",
+                _ => "value",
+            };
+            let mut wrapped = Map::new();
+            wrapped.insert("type".to_string(), json!(kind));
+            let value = match attribute.value {
+                Value::String(_) if is_date_time && value_member == "value" => {
+                    json!({"@type": "DateTime", "@value": attribute.value})
+                }
+                value => value,
+            };
+            wrapped.insert(value_member.to_string(), value);
+            if let Some(observed_at) = attribute.observed_at {
+                wrapped.insert("observedAt".to_string(), observed_at);
+            }
+            if let Some(unit_code) = attribute.unit_code {
+                wrapped.insert("unitCode".to_string(), unit_code);
+            }
+            wrapped.extend(attribute.members);
+            for (key, sub_attribute) in attribute.sub_attributes {
+                let sub_name = format!("{name}.{key}");
+                let written = write(&sub_name, None, sub_attribute, to, notes);
+                wrapped.insert(key, written);
+            }
+            Value::Object(wrapped)
+        }
+        Format::V2Normalized => {
+            let typ = match field {
+                Some(field) => v2_type(field).to_string(),
+                None => match kind {
+                    Some("GeoProperty") => String::from("geo:json"),
+                    Some(kind) if kind == "Relationship" || !LD_PROPERTY_KINDS.contains(&kind) => {
+                        kind.to_string()
+                    }
+                    _ => guess_v2_type(&attribute.value).to_string(),
+                },
+            };
+            if let Some(kind) = kind
+                .filter(|kind| ["LanguageProperty", "VocabProperty", "ListProperty"].contains(kind))
+            {
+                notes.push(format!(
+                    "{name}: NGSI-v2 has no {kind}, its value becomes a {typ}"
+                ));
+            }
+            if !attribute.members.is_empty() {
+                let dropped: Vec<_> = attribute.members.keys().map(String::as_str).collect();
+                notes.push(format!(
+                    "{name}: {} dropped, NGSI-v2 attributes have no such members",
+                    enumerate(&dropped)
+                ));
+            }
+
+            let mut metadata = Map::new();
+            if let Some(unit_code) = attribute.unit_code {
+                metadata.insert(
+                    "unitCode".to_string(),
+                    json!({"type": "Text", "value": unit_code}),
+                );
+            }
+            if let Some(observed_at) = attribute.observed_at {
+                metadata.insert(
+                    "observedAt".to_string(),
+                    json!({"type": "DateTime", "value": observed_at}),
+                );
+            }
+            for (key, sub_attribute) in attribute.sub_attributes {
+                let sub_name = format!("{name}.{key}");
+                let mut written = write(&sub_name, None, sub_attribute, to, notes);
+                // Metadata have no metadata of their own
+                let nested = written
+                    .as_object_mut()
+                    .and_then(|written| written.remove("metadata"));
+                if nested.is_some_and(|nested| nested != json!({})) {
+                    notes.push(format!("{sub_name}: its metadata are dropped"));
+                }
+                metadata.insert(key, written);
+            }
+            json!({"type": typ, "value": attribute.value, "metadata": metadata})
+        }
+    }
+}
+
+/// Lists names like `a, b and c is/are`.
+fn enumerate(names: &[&str]) -> String {
+    match names {
+        [name] => format!("{name} is"),
+        [rest @ .., last] => format!("{} and {last} are", rest.join(", ")),
+        [] => String::new(),
+    }
+}
+
+/// The NGSI-v2 attribute type of values of `field`.
+fn v2_type(field: &FieldType) -> &'static str {
+    match field {
+        FieldType::Relationship(_) => "Relationship",
+        FieldType::Geo(_) => "geo:json",
+        FieldType::DateTime => "DateTime",
+        FieldType::Integer | FieldType::Number => "Number",
+        FieldType::Boolean => "Boolean",
+        FieldType::Object | FieldType::Array(_) => "StructuredValue",
+        FieldType::String
+        | FieldType::Date
+        | FieldType::Time
+        | FieldType::Uri
+        | FieldType::Enum(_) => "Text",
+    }
+}
+
+fn guess_v2_type(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "Text",
+        Value::Number(_) => "Number",
+        Value::Bool(_) => "Boolean",
+        _ => "StructuredValue",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        ngsi::Format,
+        smartdata::models::{ParsedModel, Property},
+    };
+
+    use super::{convert, detect};
+
+    #[test]
+    fn convert_representations() {
+        let model = ParsedModel {
+            name: "WeatherObserved".to_string(),
+            properties: vec![
+                Property::from_yaml("dateObserved", "{type: string, format: date-time}"),
+                Property::from_yaml("location", "{}"),
+                Property::from_yaml("refDevice", "{type: string, x-ngsi: {type: Relationship}}"),
+                Property::from_yaml("temperature", "{type: number}"),
+            ],
+            ..Default::default()
+        };
+        let v2 = json!({
+            "id": "urn:ngsi-ld:WeatherObserved:1",
+            "type": "WeatherObserved",
+            "dateObserved": {"type": "DateTime", "value": "2024-01-01T00:00:00Z", "metadata": {}},
+            "location": {
+                "type": "geo:json",
+                "value": {"type": "Point", "coordinates": [8.5, 47.3]},
+                "metadata": {},
+            },
+            "refDevice": {"type": "Relationship", "value": "urn:ngsi-ld:Device:1", "metadata": {}},
+            "temperature": {
+                "type": "Number",
+                "value": 21.5,
+                "metadata": {
+                    "unitCode": {"type": "Text", "value": "CEL"},
+                    "TimeInstant": {"type": "DateTime", "value": "2024-01-01T00:00:00Z"},
+                    "accuracy": {"type": "Number", "value": 0.5},
+                },
+            },
+            "note": {"type": "Text", "value": "calm", "metadata": {}},
+        });
+        assert_eq!(detect(&v2), Format::V2Normalized);
+
+        let (ld, notes) =
+            convert(Some(&model), &v2, Format::V2Normalized, Format::Normalized).unwrap();
+        assert!(notes.is_empty());
+        assert_eq!(
+            ld,
+            json!({
+                "id": "urn:ngsi-ld:WeatherObserved:1",
+                "type": "WeatherObserved",
+                "dateObserved": {
+                    "type": "Property",
+                    "value": {"@type": "DateTime", "@value": "2024-01-01T00:00:00Z"},
+                },
+                "location": {
+                    "type": "GeoProperty",
+                    "value": {"type": "Point", "coordinates": [8.5, 47.3]},
+                },
+                "refDevice": {"type": "Relationship", "object": "urn:ngsi-ld:Device:1"},
+                "temperature": {
+                    "type": "Property",
+                    "value": 21.5,
+                    "observedAt": "2024-01-01T00:00:00Z",
+                    "unitCode": "CEL",
+                    "accuracy": {"type": "Property", "value": 0.5},
+                },
+                "note": {"type": "Property", "value": "calm"},
+                "@context": "https://uri.etsi.org/ngsi-ld/v1/ngsi-ld-core-context.jsonld",
+            })
+        );
+        assert_eq!(detect(&ld), Format::Normalized);
+
+        let (back, _) =
+            convert(Some(&model), &ld, Format::Normalized, Format::V2Normalized).unwrap();
+        assert_eq!(
+            back["temperature"]["metadata"]["unitCode"],
+            json!({"type": "Text", "value": "CEL"})
+        );
+        assert_eq!(
+            back["temperature"]["metadata"]["accuracy"],
+            v2["temperature"]["metadata"]["accuracy"]
+        );
+        assert_eq!(back["note"], v2["note"]);
+        assert_eq!(back["location"], v2["location"]);
+        assert!(back.get("@context").is_none());
+
+        let (key_values, notes) =
+            convert(Some(&model), &ld, Format::Normalized, Format::KeyValues).unwrap();
+        assert_eq!(key_values["temperature"], json!(21.5));
+        assert_eq!(key_values["dateObserved"], json!("2024-01-01T00:00:00Z"));
+        assert_eq!(
+            notes,
+            ["temperature: unitCode, observedAt and accuracy are dropped, key-values have no metadata"]
+        );
+        assert_eq!(detect(&key_values), Format::KeyValues);
+
+        let ld = json!({
+            "id": "urn:ngsi-ld:WeatherObserved:2",
+            "type": "WeatherObserved",
+            "name": {"type": "LanguageProperty", "languageMap": {"en": "North", "de": "Nord"}},
+            "temperature": {
+                "type": "Property",
+                "value": 19,
+                "datasetId": "urn:ngsi-ld:Dataset:a",
+                "providedBy": {"type": "Relationship", "object": "urn:ngsi-ld:Device:2"},
+            },
+            "@context": "https://uri.etsi.org/ngsi-ld/v1/ngsi-ld-core-context.jsonld",
+        });
+        let (same, notes) =
+            convert(Some(&model), &ld, Format::Normalized, Format::Normalized).unwrap();
+        assert_eq!(same, ld);
+        assert!(notes.is_empty());
+
+        let (v2, notes) =
+            convert(Some(&model), &ld, Format::Normalized, Format::V2Normalized).unwrap();
+        assert_eq!(
+            v2["temperature"]["metadata"]["providedBy"],
+            json!({"type": "Relationship", "value": "urn:ngsi-ld:Device:2"})
+        );
+        assert_eq!(
+            notes,
+            [
+                "name: NGSI-v2 has no LanguageProperty, its value becomes a StructuredValue",
+                "temperature: datasetId is dropped, NGSI-v2 attributes have no such members",
+            ]
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    codegen::types::FieldType,
+    ngsi::{conformance::short_name, is_kind, ENTITY_MEMBERS},
+    smartdata::models::ParsedModel,
+};
+
+/// A model which could be the model of an entity, with how its attributes line up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub model: String,
+    pub repo: String,
+    /// Between 0 and 1, the share of the weighted attributes which match.
+    pub score: f64,
+    /// Attributes with a property of the same name and a value of a fitting shape.
+    pub matched: Vec<String>,
+    /// Attributes with a property of the same name, whose value does not fit it.
+    pub mismatched: Vec<String>,
+    pub missing_required: Vec<String>,
+    /// Attributes the model does not define.
+    pub extra: Vec<String>,
+}
+
+/// The property names of a set of models, with the number of models defining each.
+pub struct PropertyIndex<'a> {
+    models: Vec<&'a ParsedModel>,
+    definitions: HashMap<&'a str, usize>,
+}
+
+impl<'a> PropertyIndex<'a> {
+    pub fn new(models: Vec<&'a ParsedModel>) -> Self {
+        let mut definitions = HashMap::new();
+        for model in models.iter() {
+            for property in model.properties.iter() {
+                *definitions.entry(property.name.as_str()).or_default() += 1;
+            }
+        }
+        PropertyIndex {
+            models,
+            definitions,
+        }
+    }
+
+    /// Properties most models define, like `name` or `dateCreated`, say little about the model
+    /// of an entity, rare ones say a lot.
+    fn weight(&self, name: &str) -> f64 {
+        let definitions = self.definitions.get(name).copied().unwrap_or_default();
+        (1.0 + self.models.len() as f64 / definitions.max(1) as f64).ln()
+    }
+
+    /// The models sharing attributes with `entity`, the best first. The entity may be given in
+    /// key-values form or normalized, only the attributes are compared, not its type.
+    pub fn candidates(&self, entity: &Value, limit: usize) -> Vec<Candidate> {
+        let Some(members) = entity.as_object() else {
+            return vec![];
+        };
+        let attributes: Vec<_> = members
+            .iter()
+            .filter(|(name, _)| !ENTITY_MEMBERS.contains(&name.as_str()))
+            .map(|(name, attribute)| (short_name(name), attribute))
+            .collect();
+
+        let mut candidates: Vec<_> = self
+            .models
+            .iter()
+            .filter(|model| attributes.iter().any(|(n, _)| model.property(n).is_some()))
+            .map(|model| self.candidate(model, &attributes))
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.model.cmp(&b.model))
+        });
+        candidates.truncate(limit);
+        candidates
+    }
+
+    fn candidate(&self, model: &ParsedModel, attributes: &[(&str, &Value)]) -> Candidate {
+        let mut candidate = Candidate {
+            model: model.name.clone(),
+            repo: model.repo.clone(),
+            score: 0.0,
+            matched: vec![],
+            mismatched: vec![],
+            missing_required: vec![],
+            extra: vec![],
+        };
+        let mut found = 0.0;
+        let mut total = 0.0;
+
+        for (name, attribute) in attributes {
+            let weight = self.weight(name);
+            total += weight;
+            match model.property(name) {
+                Some(property) if fits(&FieldType::from_property(property), attribute) => {
+                    found += weight;
+                    candidate.matched.push(name.to_string());
+                }
+                Some(_) => {
+                    found += weight / 2.0;
+                    candidate.mismatched.push(name.to_string());
+                }
+                None => candidate.extra.push(name.to_string()),
+            }
+        }
+        for property in model.properties.iter().filter(|p| p.required) {
+            let name = property.name.as_str();
+            if !ENTITY_MEMBERS.contains(&name) && !attributes.iter().any(|(n, _)| *n == name) {
+                total += self.weight(name);
+                candidate.missing_required.push(name.to_string());
+            }
+        }
+
+        if total > 0.0 {
+            candidate.score = found / total;
+        }
+        candidate
+    }
+}
+
+/// Whether an attribute fits a property of `field`, normalized attributes have to be of its kind.
+fn fits(field: &FieldType, attribute: &Value) -> bool {
+    let value = match attribute {
+        Value::Object(wrapped)
+            if wrapped.contains_key("value") || wrapped.contains_key("object") =>
+        {
+            let kind = wrapped["type"].as_str().unwrap_or_default();
+            if !is_kind(kind, field.ngsi_kind()) {
+                return false;
+            }
+            wrapped.get("value").or(wrapped.get("object")).unwrap()
+        }
+        _ => attribute,
+    };
+    // Normalized date-times are typed json-ld values
+    let value = value.get("@value").unwrap_or(value);
+
+    match (field, value) {
+        (FieldType::Geo(_), Value::Object(geometry)) => {
+            geometry.contains_key("coordinates") || geometry.contains_key("geometries")
+        }
+        (FieldType::Relationship(_), Value::String(_) | Value::Array(_)) => true,
+        (FieldType::Integer, Value::Number(number)) => number.is_i64() || number.is_u64(),
+        (FieldType::Number, Value::Number(_)) => true,
+        (FieldType::Boolean, Value::Bool(_)) => true,
+        (
+            FieldType::String
+            | FieldType::DateTime
+            | FieldType::Date
+            | FieldType::Time
+            | FieldType::Uri
+            | FieldType::Enum(_),
+            Value::String(_),
+        ) => true,
+        (FieldType::Object, Value::Object(_)) => true,
+        (FieldType::Array(_), Value::Array(_)) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::PropertyIndex;
+
+    #[test]
+    fn rank_candidates() {
+        let model = |name: &str, properties: Vec<Property>| ParsedModel {
+            name: name.to_string(),
+            repo: name.to_string(),
+            properties,
+            ..Default::default()
+        };
+        let models = [
+            model(
+                "AirQualityObserved",
+                vec![
+                    Property::from_yaml("name", "{type: string}"),
+                    Property::from_yaml("no2", "{type: number}"),
+                    Property::from_yaml("pm10", "{type: number}"),
+                    Property::from_yaml("location", "{}"),
+                ],
+            ),
+            model(
+                "Device",
+                vec![
+                    Property::from_yaml("name", "{type: string}"),
+                    Property::from_yaml("batteryLevel", "{type: number}"),
+                    Property {
+                        required: true,
+                        ..Property::from_yaml("controlledProperty", "{type: array}")
+                    },
+                ],
+            ),
+            model(
+                "Building",
+                vec![Property::from_yaml("address", "{type: object}")],
+            ),
+        ];
+        let index = PropertyIndex::new(models.iter().collect());
+
+        let entity = json!({
+            "id": "sensor-1",
+            "name": "Main street",
+            "no2": {"type": "Property", "value": 22.5, "unitCode": "GQ"},
+            "pm10": "high",
+            "location": {"type": "Point", "coordinates": [8.5, 47.3]},
+            "batteryLevel": 0.8,
+        });
+        let candidates = index.candidates(&entity, 5);
+        let names: Vec<_> = candidates.iter().map(|c| c.model.as_str()).collect();
+        assert_eq!(names, ["AirQualityObserved", "Device"]);
+
+        let best = &candidates[0];
+        assert_eq!(best.matched, ["name", "no2", "location"]);
+        assert_eq!(best.mismatched, ["pm10"]);
+        assert_eq!(best.extra, ["batteryLevel"]);
+        assert!(best.score > 0.5 && best.score < 1.0);
+
+        let device = &candidates[1];
+        assert_eq!(device.matched, ["name", "batteryLevel"]);
+        assert_eq!(device.missing_required, ["controlledProperty"]);
+        assert!(device.score < best.score);
+    }
+}
@@ -0,0 +1,573 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    codegen::types::FieldType,
+    ngsi::{
+        builder::{EntityForm, FieldError},
+        Format,
+    },
+    smartdata::{
+        models::{ParsedModel, Property},
+        refs::DEFAULT_REF,
+    },
+    ModelData,
+};
+
+/// How the columns of a csv file become entities of a model, saved as a yaml file which the
+/// CLI applies in batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsvMapping {
+    pub repo: String,
+    pub model: String,
+    #[serde(rename = "ref", default = "default_ref")]
+    pub git_ref: String,
+    /// The id of the entity of a row, `{column}` is replaced by the value of the column and
+    /// `{row}` by the number of the row, starting at 1.
+    pub id: String,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// The representation the entities are written in.
+    #[serde(default)]
+    pub format: Format,
+    #[serde(default)]
+    pub columns: Vec<ColumnMapping>,
+    /// Where the file was opened from or saved to.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    pub column: String,
+    pub property: String,
+    #[serde(default, skip_serializing_if = "Conversion::is_none")]
+    pub conversion: Conversion,
+}
+
+fn default_ref() -> String {
+    DEFAULT_REF.to_string()
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+/// How the text of a cell is converted before it is parsed as a value of its property.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Conversion {
+    #[default]
+    None,
+    /// The cell is the longitude of a point, another column has the latitude.
+    Longitude,
+    Latitude,
+    PercentToRatio,
+    FahrenheitToCelsius,
+    KelvinToCelsius,
+    KilometersPerHourToMetersPerSecond,
+    /// Numbers written with a decimal comma, like `1,5`.
+    DecimalComma,
+    /// Seconds since 1970 to a date-time in UTC.
+    UnixTime,
+}
+
+impl Conversion {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Conversion::None => "none",
+            Conversion::Longitude => "longitude",
+            Conversion::Latitude => "latitude",
+            Conversion::PercentToRatio => "% to ratio",
+            Conversion::FahrenheitToCelsius => "°F to °C",
+            Conversion::KelvinToCelsius => "K to °C",
+            Conversion::KilometersPerHourToMetersPerSecond => "km/h to m/s",
+            Conversion::DecimalComma => "decimal comma",
+            Conversion::UnixTime => "unix time",
+        }
+    }
+
+    pub fn array() -> [Conversion; 9] {
+        [
+            Conversion::None,
+            Conversion::Longitude,
+            Conversion::Latitude,
+            Conversion::PercentToRatio,
+            Conversion::FahrenheitToCelsius,
+            Conversion::KelvinToCelsius,
+            Conversion::KilometersPerHourToMetersPerSecond,
+            Conversion::DecimalComma,
+            Conversion::UnixTime,
+        ]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Conversion::array().into_iter().find(|c| c.str() == name)
+    }
+
+    fn is_none(&self) -> bool {
+        *self == Conversion::None
+    }
+
+    /// The conversions which make sense for the type and units of `property`.
+    pub fn suggestions(property: &Property) -> Vec<Conversion> {
+        let mut suggestions = vec![Conversion::None];
+        match FieldType::from_property(property) {
+            FieldType::Geo(_) => return vec![Conversion::Longitude, Conversion::Latitude],
+            FieldType::DateTime => suggestions.push(Conversion::UnixTime),
+            FieldType::Integer | FieldType::Number => {
+                suggestions.push(Conversion::DecimalComma);
+                if property.maximum == Some(1.0) {
+                    suggestions.push(Conversion::PercentToRatio);
+                }
+                match property.units() {
+                    Some("CEL") => suggestions
+                        .extend([Conversion::FahrenheitToCelsius, Conversion::KelvinToCelsius]),
+                    Some("MTS") => suggestions.push(Conversion::KilometersPerHourToMetersPerSecond),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        suggestions
+    }
+
+    /// Converts the text of a cell, empty cells stay empty.
+    fn apply(&self, cell: &str) -> Result<String, String> {
+        let cell = cell.trim();
+        if cell.is_empty() {
+            return Ok(String::new());
+        }
+        let number = || {
+            cell.parse::<f64>()
+                .map_err(|_| format!("{cell} is no number"))
+        };
+        Ok(match self {
+            Conversion::None | Conversion::Longitude | Conversion::Latitude => cell.to_string(),
+            Conversion::PercentToRatio => (number()? / 100.0).to_string(),
+            Conversion::FahrenheitToCelsius => ((number()? - 32.0) * 5.0 / 9.0).to_string(),
+            Conversion::KelvinToCelsius => (number()? - 273.15).to_string(),
+            Conversion::KilometersPerHourToMetersPerSecond => (number()? / 3.6).to_string(),
+            Conversion::DecimalComma => cell.replace('.', "").replace(',', "."),
+            Conversion::UnixTime => {
+                let seconds = cell
+                    .parse::<i64>()
+                    .map_err(|_| format!("{cell} is no unix time"))?;
+                unix_time(seconds)
+            }
+        })
+    }
+}
+
+/// The type and units a column of `property` is expected to have.
+pub fn hint(property: &Property) -> String {
+    let typ = match FieldType::from_property(property) {
+        FieldType::Integer => "whole number",
+        FieldType::Number => "number",
+        FieldType::Boolean => "true or false",
+        FieldType::DateTime => "date-time",
+        FieldType::Date => "date",
+        FieldType::Time => "time",
+        FieldType::Uri => "uri",
+        FieldType::Enum(_) => "one of the enum values",
+        FieldType::Relationship(_) => "entity id",
+        FieldType::Geo(_) => "longitude and latitude columns",
+        FieldType::Object | FieldType::Array(_) => "json",
+        FieldType::String => "text",
+    };
+    match property.units() {
+        Some(units) => format!("{typ} in {units}"),
+        None => typ.to_string(),
+    }
+}
+
+/// The header and rows of a csv file.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CsvTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    pub fn parse(text: &str, delimiter: char) -> Result<Self, anyhow::Error> {
+        let delimiter = u8::try_from(delimiter)
+            .map_err(|_| anyhow::anyhow!("the delimiter must be a single byte character"))?;
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .from_reader(text.as_bytes());
+        let headers = reader.headers()?.iter().map(str::to_string).collect();
+        let mut rows = vec![];
+        for record in reader.records() {
+            rows.push(record?.iter().map(str::to_string).collect());
+        }
+        Ok(CsvTable { headers, rows })
+    }
+
+    pub fn load(path: &Path, delimiter: char) -> Result<Self, anyhow::Error> {
+        CsvTable::parse(&fs::read_to_string(path)?, delimiter)
+    }
+}
+
+/// The entity of a row, with the errors which keep it from being valid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowResult {
+    /// Starting at 1 for the first row after the header.
+    pub row: usize,
+    pub entity: Value,
+    pub errors: Vec<FieldError>,
+}
+
+impl CsvMapping {
+    pub fn new(model: &ParsedModel) -> Self {
+        CsvMapping {
+            repo: model.repo.clone(),
+            model: model.name.clone(),
+            git_ref: model.git_ref.clone(),
+            id: format!("urn:ngsi-ld:{}:{{row}}", model.name),
+            delimiter: default_delimiter(),
+            format: Format::Normalized,
+            columns: vec![],
+            path: None,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let yaml = fs::read_to_string(path)?;
+        let mut mapping: CsvMapping = serde_yaml::from_str(&yaml)?;
+        mapping.path = Some(path.to_path_buf());
+        Ok(mapping)
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<(), anyhow::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_yaml::to_string(self)?)?;
+        self.path = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    pub fn model_data(&self) -> ModelData {
+        ModelData {
+            repo: self.repo.clone(),
+            name: self.model.clone(),
+            git_ref: self.git_ref.clone(),
+        }
+    }
+
+    pub fn column(&self, column: &str) -> Option<&ColumnMapping> {
+        self.columns.iter().find(|c| c.column == column)
+    }
+
+    /// Maps `column` to `property` with a suggested conversion, an empty property unmaps it.
+    pub fn map_column(&mut self, column: &str, property: Option<&Property>) {
+        self.columns.retain(|c| c.column != column);
+        if let Some(property) = property {
+            let conversion = match Conversion::suggestions(property).as_slice() {
+                [Conversion::Longitude, ..] if is_latitude(column) => Conversion::Latitude,
+                [first, ..] => *first,
+                [] => Conversion::None,
+            };
+            self.columns.push(ColumnMapping {
+                column: column.to_string(),
+                property: property.name.clone(),
+                conversion,
+            });
+        }
+    }
+
+    pub fn set_conversion(&mut self, column: &str, conversion: Conversion) {
+        if let Some(mapping) = self.columns.iter_mut().find(|c| c.column == column) {
+            mapping.conversion = conversion;
+        }
+    }
+
+    /// Maps the unmapped columns, whose names match a property of `model` when case, `_` and
+    /// `-` are ignored. Coordinate columns like `lon` and `lat` go to `location`.
+    pub fn suggest(&mut self, model: &ParsedModel, headers: &[String]) {
+        let key = |name: &str| name.to_lowercase().replace(['_', '-', ' '], "");
+        for header in headers {
+            if self.column(header).is_some() {
+                continue;
+            }
+            let property = model
+                .properties
+                .iter()
+                .find(|p| key(&p.name) == key(header))
+                .or_else(|| {
+                    let is_coordinate = is_latitude(header)
+                        || ["lon", "lng", "long", "longitude", "x"].contains(&key(header).as_str());
+                    model.property("location").filter(|_| is_coordinate)
+                });
+            if property.is_some() {
+                self.map_column(header, property);
+            }
+        }
+    }
+
+    /// Converts every row of `table` into an entity of `model`. The checked properties and
+    /// the mapped ones are validated, like the inputs of the entity builder.
+    pub fn convert(
+        &self,
+        model: &ParsedModel,
+        table: &CsvTable,
+    ) -> Result<Vec<RowResult>, anyhow::Error> {
+        let position = |column: &str| {
+            table
+                .headers
+                .iter()
+                .position(|header| header == column)
+                .ok_or_else(|| anyhow::anyhow!("the file has no column {column}"))
+        };
+        let mut columns = vec![];
+        for mapping in self.columns.iter() {
+            if model.property(&mapping.property).is_none() {
+                anyhow::bail!("{} has no property {}", model.name, mapping.property);
+            }
+            columns.push((position(&mapping.column)?, mapping));
+        }
+        let id_parts = template_parts(&self.id)?;
+        for part in id_parts.iter() {
+            if let Part::Column(column) = part {
+                position(column)?;
+            }
+        }
+
+        let mut model = model.clone();
+        for property in model.properties.iter_mut() {
+            property.checked |= self.columns.iter().any(|c| c.property == property.name);
+        }
+
+        let mut results = Vec::with_capacity(table.rows.len());
+        for (i, cells) in table.rows.iter().enumerate() {
+            let row = i + 1;
+            let cell = |index: usize| cells.get(index).map(String::as_str).unwrap_or_default();
+            let mut form = EntityForm::default();
+            let mut conversion_errors = vec![];
+
+            for part in id_parts.iter() {
+                match part {
+                    Part::Text(text) => form.id.push_str(text),
+                    Part::Row => form.id.push_str(&row.to_string()),
+                    Part::Column(column) => form.id.push_str(cell(position(column)?).trim()),
+                }
+            }
+
+            for (index, mapping) in columns.iter() {
+                let value = match mapping.conversion.apply(cell(*index)) {
+                    Ok(value) => value,
+                    Err(message) => {
+                        conversion_errors.push(FieldError {
+                            property: mapping.property.clone(),
+                            message: format!("column {}: {message}", mapping.column),
+                        });
+                        continue;
+                    }
+                };
+                let axis = match mapping.conversion {
+                    Conversion::Longitude => 0,
+                    Conversion::Latitude => 1,
+                    _ => {
+                        form.inputs.insert(mapping.property.clone(), value);
+                        continue;
+                    }
+                };
+                // Rows without coordinates leave the geometry out
+                if !value.is_empty() {
+                    let positions = form.positions.entry(mapping.property.clone()).or_default();
+                    if positions.is_empty() {
+                        positions.push(Default::default());
+                    }
+                    positions[0][axis] = value;
+                }
+            }
+
+            let (entity, mut errors) = form.document(&model, self.format);
+            // A failed conversion is reported instead of the missing value it leaves behind
+            errors.retain(|e| !conversion_errors.iter().any(|c| c.property == e.property));
+            errors.extend(conversion_errors);
+            results.push(RowResult {
+                row,
+                entity,
+                errors,
+            });
+        }
+        Ok(results)
+    }
+}
+
+fn is_latitude(column: &str) -> bool {
+    ["lat", "latitude", "y"].contains(&column.to_lowercase().as_str())
+}
+
+enum Part {
+    Text(String),
+    Row,
+    Column(String),
+}
+
+/// Splits an id template into its text and its placeholders.
+fn template_parts(template: &str) -> Result<Vec<Part>, anyhow::Error> {
+    let mut parts = vec![];
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            anyhow::bail!("the id template has a {{ without }}");
+        };
+        parts.push(Part::Text(rest[..start].to_string()));
+        parts.push(match &rest[start + 1..start + end] {
+            "row" => Part::Row,
+            column => Part::Column(column.to_string()),
+        });
+        rest = &rest[start + end + 1..];
+    }
+    parts.push(Part::Text(rest.to_string()));
+    Ok(parts)
+}
+
+/// A date-time in UTC of the seconds since 1970, with the civil calendar algorithm of
+/// Howard Hinnant.
+fn unix_time(seconds: i64) -> String {
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        ngsi::Format,
+        smartdata::models::{ParsedModel, Property},
+    };
+
+    use super::{Conversion, CsvMapping, CsvTable};
+
+    #[test]
+    fn convert_rows() {
+        let model = ParsedModel {
+            name: "WeatherObserved".to_string(),
+            repo: "Weather".to_string(),
+            git_ref: "master".to_string(),
+            properties: vec![
+                Property {
+                    required: true,
+                    ..Property::from_yaml("dateObserved", "{type: string, format: date-time}")
+                },
+                Property {
+                    checked: false,
+                    ..Property::from_yaml("location", "{}")
+                },
+                Property {
+                    checked: false,
+                    ..Property::from_yaml(
+                        "temperature",
+                        "{type: number, x-ngsi: {type: Property, units: CEL}}",
+                    )
+                },
+                Property {
+                    checked: false,
+                    ..Property::from_yaml("relativeHumidity", "{type: number, maximum: 1}")
+                },
+            ],
+            ..Default::default()
+        };
+        let table = CsvTable::parse(
+            "station;time;temp_f;humidity;lat;lon\n\
+            north;1704067200;212;55;47.3;8.5\n\
+            south;;warm;;;\n",
+            ';',
+        )
+        .unwrap();
+
+        let mut mapping = CsvMapping::new(&model);
+        mapping.delimiter = ';';
+        mapping.format = Format::KeyValues;
+        mapping.id = "urn:ngsi-ld:WeatherObserved:{station}-{row}".to_string();
+        mapping.suggest(&model, &table.headers);
+        mapping.map_column("time", model.property("dateObserved"));
+        mapping.set_conversion("time", Conversion::UnixTime);
+        mapping.map_column("temp_f", model.property("temperature"));
+        mapping.set_conversion("temp_f", Conversion::FahrenheitToCelsius);
+        mapping.map_column("humidity", model.property("relativeHumidity"));
+        assert_eq!(
+            mapping.column("lat").unwrap().conversion,
+            Conversion::Latitude
+        );
+        assert_eq!(
+            Conversion::suggestions(model.property("relativeHumidity").unwrap()),
+            [
+                Conversion::None,
+                Conversion::DecimalComma,
+                Conversion::PercentToRatio
+            ]
+        );
+        mapping.set_conversion("humidity", Conversion::PercentToRatio);
+
+        let results = mapping.convert(&model, &table).unwrap();
+        assert_eq!(
+            results[0].entity,
+            json!({
+                "id": "urn:ngsi-ld:WeatherObserved:north-1",
+                "type": "WeatherObserved",
+                "dateObserved": "2024-01-01T00:00:00Z",
+                "location": {"type": "Point", "coordinates": [8.5, 47.3]},
+                "temperature": 100.0,
+                "relativeHumidity": 0.55,
+                "@context": [
+                    "https://raw.githubusercontent.com/smart-data-models/dataModel.Weather/master/context.jsonld",
+                    "https://uri.etsi.org/ngsi-ld/v1/ngsi-ld-core-context.jsonld",
+                ],
+            })
+        );
+        assert!(results[0].errors.is_empty());
+
+        let messages: Vec<_> = results[1]
+            .errors
+            .iter()
+            .map(|e| format!("{} {}", e.property, e.message))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "dateObserved is required",
+                "temperature column temp_f: warm is no number",
+            ]
+        );
+
+        let yaml = serde_yaml::to_string(&mapping).unwrap();
+        assert!(yaml.contains("conversion: fahrenheit-to-celsius"));
+        assert_eq!(serde_yaml::from_str::<CsvMapping>(&yaml).unwrap(), mapping);
+
+        mapping.id = "urn:ngsi-ld:WeatherObserved:{name}".to_string();
+        assert!(mapping.convert(&model, &table).is_err());
+    }
+}
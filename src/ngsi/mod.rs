@@ -0,0 +1,179 @@
+//! NGSI-LD entities of the smart data models, in the representations brokers accept.
+
+pub mod builder;
+pub mod conformance;
+pub mod convert;
+pub mod inference;
+pub mod mapping;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{ngsi::convert::convert, smartdata::models::ParsedModel};
+
+pub const CORE_CONTEXT: &str = "https://uri.etsi.org/ngsi-ld/v1/ngsi-ld-core-context.jsonld";
+
+/// Members of normalized entities which are no attributes.
+pub const ENTITY_MEMBERS: [&str; 7] = [
+    "id",
+    "type",
+    "@context",
+    "scope",
+    "createdAt",
+    "modifiedAt",
+    "deletedAt",
+];
+
+/// The representations of an entity.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Format {
+    /// Every attribute is an object with its kind, e.g. `{"type": "Property", "value": 1}`.
+    #[default]
+    Normalized,
+    /// Attributes are plain values.
+    KeyValues,
+    /// Every attribute is an object with its type and metadata, e.g.
+    /// `{"type": "Number", "value": 1, "metadata": {}}`.
+    V2Normalized,
+}
+
+impl Format {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Format::Normalized => "NGSI-LD normalized",
+            Format::KeyValues => "Key-values",
+            Format::V2Normalized => "NGSI-v2 normalized",
+        }
+    }
+
+    /// The name in mapping files and on the command line.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Format::Normalized => "normalized",
+            Format::KeyValues => "key-values",
+            Format::V2Normalized => "v2-normalized",
+        }
+    }
+
+    pub fn array() -> [Format; 3] {
+        [Format::Normalized, Format::KeyValues, Format::V2Normalized]
+    }
+
+    pub fn from_name(format: &str) -> Option<Self> {
+        Format::array().into_iter().find(|f| f.str() == format)
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Format::array().into_iter().find(|f| f.key() == key)
+    }
+}
+
+/// The `@context` of the entities of `model`, the context of its repository at the ref the model
+/// was fetched at and the core context.
+pub fn context(model: &ParsedModel) -> Value {
+    if model.repo.is_empty() {
+        return json!(CORE_CONTEXT);
+    }
+    json!([
+        format!(
+            "https://raw.githubusercontent.com/smart-data-models/dataModel.{}/{}/context.jsonld",
+            model.repo,
+            model.fetched_ref()
+        ),
+        CORE_CONTEXT,
+    ])
+}
+
+/// An entity of `model` with `attributes` given in key-values form, in the representation of
+/// `format`. The kinds of the properties decide how values are wrapped.
+pub fn document(
+    model: &ParsedModel,
+    id: &str,
+    attributes: &Map<String, Value>,
+    format: Format,
+) -> Value {
+    let mut entity = Map::new();
+    entity.insert("id".to_string(), json!(id));
+    entity.insert("type".to_string(), json!(model.name));
+    entity.extend(attributes.clone());
+    entity.insert("@context".to_string(), context(model));
+    convert(
+        Some(model),
+        &Value::Object(entity),
+        Format::KeyValues,
+        format,
+    )
+    .expect("the entity is an object")
+    .0
+}
+
+/// Whether an attribute of kind `found` is an `expected` one. Language, vocabulary and list
+/// properties are properties, which models do not tell apart.
+pub fn is_kind(found: &str, expected: &str) -> bool {
+    found == expected
+        || expected == "Property"
+            && ["LanguageProperty", "VocabProperty", "ListProperty"].contains(&found)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Map};
+
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::{context, document, Format, CORE_CONTEXT};
+
+    #[test]
+    fn normalized_document() {
+        let model = ParsedModel {
+            name: "Device".to_string(),
+            properties: vec![
+                Property::from_yaml(
+                    "refOwner",
+                    "{type: string, format: uri, x-ngsi: {type: Relationship}}",
+                ),
+                Property::from_yaml("dateCreated", "{type: string, format: date-time}"),
+            ],
+            ..Default::default()
+        };
+        let mut attributes = Map::new();
+        attributes.insert("refOwner".to_string(), json!("urn:ngsi-ld:Person:1"));
+        attributes.insert("dateCreated".to_string(), json!("2024-01-01T00:00:00Z"));
+        attributes.insert("note".to_string(), json!("new"));
+
+        assert_eq!(
+            document(
+                &model,
+                "urn:ngsi-ld:Device:1",
+                &attributes,
+                Format::Normalized
+            ),
+            json!({
+                "id": "urn:ngsi-ld:Device:1",
+                "type": "Device",
+                "refOwner": {"type": "Relationship", "object": "urn:ngsi-ld:Person:1"},
+                "dateCreated": {
+                    "type": "Property",
+                    "value": {"@type": "DateTime", "@value": "2024-01-01T00:00:00Z"},
+                },
+                "note": {"type": "Property", "value": "new"},
+                "@context": CORE_CONTEXT,
+            })
+        );
+
+        let pinned = ParsedModel {
+            repo: "Device".to_string(),
+            git_ref: "2024-01-01".to_string(),
+            resolved_ref: "3f2a9c1".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            context(&pinned),
+            json!([
+                "https://raw.githubusercontent.com/smart-data-models/dataModel.Device/3f2a9c1/context.jsonld",
+                CORE_CONTEXT,
+            ])
+        );
+    }
+}
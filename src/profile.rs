@@ -0,0 +1,219 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::config_dir, smartdata::models::ParsedModel};
+
+/// A named selection of properties for any number of models, saved as a yaml file.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// Selections keyed by the model name.
+    #[serde(default)]
+    pub models: BTreeMap<String, ModelSelection>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelSelection {
+    pub repo: String,
+    /// Names of the checked properties.
+    #[serde(default)]
+    pub properties: Vec<String>,
+    /// Chosen variant of properties which can be one of several types, e.g. `location: Point`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variants: BTreeMap<String, String>,
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Profile {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Remembers the checked properties and chosen variants of `model`.
+    pub fn record(&mut self, model: &ParsedModel) {
+        let properties = model
+            .properties
+            .iter()
+            .filter(|property| property.checked)
+            .map(|property| property.name.clone())
+            .collect();
+        let variants = model
+            .properties
+            .iter()
+            .filter_map(|property| Some((property.name.clone(), property.variant.clone()?)))
+            .collect();
+
+        self.models.insert(
+            model.name.clone(),
+            ModelSelection {
+                repo: model.repo.clone(),
+                properties,
+                variants,
+            },
+        );
+    }
+
+    /// Checks exactly the properties of the selection, required properties always stay checked.
+    /// Returns false if the profile has no selection for `model`.
+    pub fn apply(&self, model: &mut ParsedModel) -> bool {
+        let Some(selection) = self.models.get(&model.name) else {
+            return false;
+        };
+
+        for property in model.properties.iter_mut() {
+            property.checked = property.required || selection.properties.contains(&property.name);
+            if let Some(variant) = selection.variants.get(&property.name) {
+                property.variant = Some(variant.clone());
+            }
+        }
+        true
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("profile is always serializable")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let yaml = fs::read_to_string(path)?;
+        Ok(Profile::from_yaml(&yaml)?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), anyhow::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_yaml())?;
+        Ok(())
+    }
+
+    /// Saves the profile into the profiles directory.
+    pub fn save(&self) -> Result<PathBuf, anyhow::Error> {
+        let path = profile_path(&self.name);
+        self.save_to(&path)?;
+        Ok(path)
+    }
+
+    /// Saves the profile into the profiles directory, unless a saved profile already has its
+    /// file. Different names can share a file, as unusual characters become `_`.
+    pub fn save_new(&self) -> Result<PathBuf, anyhow::Error> {
+        let path = profile_path(&self.name);
+        if path.exists() {
+            let existing = Profile::load(&path).map_or_else(|_| self.name.clone(), |p| p.name);
+            anyhow::bail!(
+                "the profile {existing} is already saved as {}, choose another name",
+                path.display()
+            );
+        }
+        self.save_to(&path)?;
+        Ok(path)
+    }
+
+    /// Loads a saved profile by its name.
+    pub fn load_saved(name: &str) -> Result<Self, anyhow::Error> {
+        Profile::load(&profile_path(name))
+    }
+
+    pub fn delete_saved(name: &str) -> Result<(), anyhow::Error> {
+        fs::remove_file(profile_path(name))?;
+        Ok(())
+    }
+
+    /// All profiles of the profiles directory sorted by name, broken files are skipped.
+    pub fn list_saved() -> Vec<Self> {
+        let Ok(entries) = fs::read_dir(profiles_dir()) else {
+            return vec![];
+        };
+
+        let mut profiles: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "yaml"))
+            .filter_map(|path| Profile::load(&path).ok())
+            .collect();
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        profiles
+    }
+}
+
+pub fn profiles_dir() -> PathBuf {
+    config_dir().join("profiles")
+}
+
+fn profile_path(name: &str) -> PathBuf {
+    let file_name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    profiles_dir().join(format!("{file_name}.yaml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::Profile;
+
+    fn model() -> ParsedModel {
+        let property = |name: &str, required: bool| Property {
+            name: name.to_string(),
+            required,
+            checked: required,
+            ..Default::default()
+        };
+
+        ParsedModel {
+            name: "WeatherObserved".to_string(),
+            repo: "Weather".to_string(),
+            properties: vec![
+                property("id", true),
+                property("temperature", false),
+                property("location", false),
+                property("pressure", false),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn record_and_apply() {
+        let mut recorded = model();
+        recorded.properties[1].checked = true;
+        recorded.properties[2].checked = true;
+        recorded.properties[2].variant = Some("Point".to_string());
+
+        let mut profile = Profile::new("minimal-sensor");
+        profile.record(&recorded);
+
+        let yaml = profile.to_yaml();
+        let profile = Profile::from_yaml(&yaml).unwrap();
+
+        let mut applied = model();
+        assert!(profile.apply(&mut applied));
+        assert_eq!(applied, recorded);
+    }
+
+    #[test]
+    fn apply_unknown_model() {
+        let profile = Profile::new("empty");
+        assert!(!profile.apply(&mut model()));
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    profile::Profile,
+    smartdata::models::{ParsedModel, Property},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    /// Defined identically in all compared models.
+    Shared,
+    /// Defined in exactly one of the compared models.
+    Unique,
+    /// Defined identically in some, but not all of the compared models.
+    Partial,
+    /// Defined in several models, but with differing definitions.
+    Different(Vec<&'static str>),
+}
+
+impl Status {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Status::Shared => "shared",
+            Status::Unique => "unique",
+            Status::Partial => "partial",
+            Status::Different(_) => "different",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparedProperty {
+    pub name: String,
+    /// One entry per compared model, in the order the models were given.
+    pub definitions: Vec<Option<Property>>,
+    pub status: Status,
+}
+
+/// Compares the properties of several models with each other.
+/// The result contains every property name of all models, sorted by name.
+pub fn compare(models: &[ParsedModel]) -> Vec<ComparedProperty> {
+    let names: BTreeSet<&str> = models
+        .iter()
+        .flat_map(|model| model.properties.iter().map(|p| p.name.as_str()))
+        .collect();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let definitions: Vec<_> = models
+                .iter()
+                .map(|model| model.property(name).cloned())
+                .collect();
+            let status = status(&definitions);
+
+            ComparedProperty {
+                name: name.to_string(),
+                definitions,
+                status,
+            }
+        })
+        .collect()
+}
+
+fn status(definitions: &[Option<Property>]) -> Status {
+    let defined: Vec<_> = definitions.iter().flatten().collect();
+    if defined.len() == 1 && definitions.len() > 1 {
+        return Status::Unique;
+    }
+
+    let mut differences: Vec<&'static str> = vec![];
+    for other in &defined[1..] {
+        for difference in defined[0].differences(other) {
+            if !differences.contains(&difference) {
+                differences.push(difference);
+            }
+        }
+    }
+
+    if !differences.is_empty() {
+        Status::Different(differences)
+    } else if defined.len() == definitions.len() {
+        Status::Shared
+    } else {
+        Status::Partial
+    }
+}
+
+/// Merges the selections of all models into a new profile. A property checked in one model is
+/// checked in every compared model which defines it as well.
+pub fn merge(models: &[ParsedModel], name: &str) -> Profile {
+    let checked: BTreeSet<&str> = models
+        .iter()
+        .flat_map(|model| model.properties.iter())
+        .filter(|property| property.checked)
+        .map(|property| property.name.as_str())
+        .collect();
+    let sources: Vec<_> = models.iter().map(|model| model.name.as_str()).collect();
+
+    let mut profile = Profile::new(name);
+    profile.description = format!("Merged from {}.", sources.join(", "));
+    for model in models {
+        let mut model = model.clone();
+        for property in model.properties.iter_mut() {
+            property.checked = checked.contains(property.name.as_str());
+        }
+        profile.record(&model);
+    }
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::{compare, merge, Status};
+
+    fn property(name: &str, typ: &str) -> Property {
+        Property {
+            name: name.to_string(),
+            typ: Some(typ.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn model(name: &str, properties: Vec<Property>) -> ParsedModel {
+        ParsedModel {
+            name: name.to_string(),
+            properties,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn classify_properties() {
+        let a = model(
+            "ParkingSpot",
+            vec![
+                property("id", "string"),
+                property("category", "array"),
+                property("width", "number"),
+            ],
+        );
+        let b = model(
+            "OffStreetParking",
+            vec![
+                property("id", "string"),
+                property("category", "string"),
+                property("totalSpotNumber", "number"),
+            ],
+        );
+
+        let compared = compare(&[a, b]);
+        let statuses: Vec<_> = compared
+            .iter()
+            .map(|c| (c.name.as_str(), c.status.clone()))
+            .collect();
+
+        assert_eq!(
+            statuses,
+            vec![
+                ("category", Status::Different(vec!["type"])),
+                ("id", Status::Shared),
+                ("totalSpotNumber", Status::Unique),
+                ("width", Status::Unique),
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_checked_properties() {
+        let mut id = property("id", "string");
+        id.checked = true;
+        id.required = true;
+        let mut name = property("name", "string");
+        name.checked = true;
+
+        let a = model("A", vec![id.clone(), property("name", "string")]);
+        let b = model("B", vec![id, name, property("width", "number")]);
+
+        let merged = merge(&[a, b], "parking");
+        assert_eq!(merged.name, "parking");
+        assert_eq!(merged.description, "Merged from A, B.");
+        assert_eq!(merged.models["A"].properties, ["id", "name"]);
+        assert_eq!(merged.models["B"].properties, ["id", "name"]);
+    }
+}
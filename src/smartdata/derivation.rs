@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use crate::smartdata::models::ParsedModel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Source {
+    SmartDataModels,
+    SchemaOrg,
+    Saref,
+    Fiware,
+    Other,
+    Unknown,
+}
+
+impl Source {
+    pub fn str(&self) -> &'static str {
+        match self {
+            Source::SmartDataModels => "Smart Data Models",
+            Source::SchemaOrg => "schema.org",
+            Source::Saref => "SAREF",
+            Source::Fiware => "FIWARE",
+            Source::Other => "Other",
+            Source::Unknown => "Unknown",
+        }
+    }
+
+    pub fn array() -> [Source; 6] {
+        [
+            Source::SmartDataModels,
+            Source::SchemaOrg,
+            Source::Saref,
+            Source::Fiware,
+            Source::Other,
+            Source::Unknown,
+        ]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Source::array()
+            .into_iter()
+            .find(|source| source.str() == name)
+    }
+}
+
+/// The parsed `x-derived-from` link of a model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Derivation {
+    pub url: String,
+    pub source: Source,
+    /// The model it derives from, if the link points to another smart data model.
+    pub parent: Option<String>,
+}
+
+impl Derivation {
+    pub fn parse(derived_from: &str) -> Self {
+        let url = derived_from.trim();
+        let lower = url.to_lowercase();
+
+        let source = if url.is_empty() {
+            Source::Unknown
+        } else if lower.contains("smart-data-models") || lower.contains("smartdatamodels") {
+            Source::SmartDataModels
+        } else if lower.contains("schema.org") {
+            Source::SchemaOrg
+        } else if lower.contains("saref") {
+            Source::Saref
+        } else if lower.contains("fiware") {
+            Source::Fiware
+        } else {
+            Source::Other
+        };
+
+        // Links look like `.../dataModel.Device/Device/schema.json`, the model follows the repo
+        let parent = match source {
+            Source::SmartDataModels => url
+                .split('/')
+                .skip_while(|segment| !segment.starts_with("dataModel."))
+                .nth(1)
+                .filter(|segment| !segment.is_empty() && !segment.contains('.'))
+                .map(str::to_string),
+            _ => None,
+        };
+
+        Derivation {
+            url: url.to_string(),
+            source,
+            parent,
+        }
+    }
+}
+
+/// Parent links between the given models, built from their `x-derived-from`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DerivationTree {
+    parents: BTreeMap<String, String>,
+}
+
+impl DerivationTree {
+    pub fn new(models: &[ParsedModel]) -> Self {
+        let parents = models
+            .iter()
+            .filter_map(|model| {
+                let parent = Derivation::parse(&model.derived_from).parent?;
+                // Many models link to their own schema
+                (parent != model.name).then(|| (model.name.clone(), parent))
+            })
+            .collect();
+
+        DerivationTree { parents }
+    }
+
+    /// The chain of models `name` derives from, starting with its direct parent.
+    pub fn ancestors(&self, name: &str) -> Vec<String> {
+        let mut ancestors: Vec<String> = vec![];
+        let mut current = name;
+        while let Some(parent) = self.parents.get(current) {
+            if parent == name || ancestors.contains(parent) {
+                break;
+            }
+            ancestors.push(parent.clone());
+            current = parent;
+        }
+        ancestors
+    }
+
+    /// All models derived from `name`, with their depth below `name`.
+    pub fn descendants(&self, name: &str) -> Vec<(usize, String)> {
+        let mut descendants = vec![];
+        self.collect_descendants(name, 1, &mut descendants);
+        descendants
+    }
+
+    fn collect_descendants(&self, name: &str, depth: usize, out: &mut Vec<(usize, String)>) {
+        for (child, parent) in self.parents.iter() {
+            if parent == name && !out.iter().any(|(_, n)| n == child) {
+                out.push((depth, child.clone()));
+                self.collect_descendants(child, depth + 1, out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::ParsedModel;
+
+    use super::{Derivation, DerivationTree, Source};
+
+    #[test]
+    fn parse_links() {
+        let derivation = Derivation::parse(
+            "https://smart-data-models.github.io/dataModel.Device/Device/schema.json",
+        );
+        assert_eq!(derivation.source, Source::SmartDataModels);
+        assert_eq!(derivation.parent.as_deref(), Some("Device"));
+
+        let derivation = Derivation::parse("https://schema.org/Thing");
+        assert_eq!(derivation.source, Source::SchemaOrg);
+        assert_eq!(derivation.parent, None);
+
+        assert_eq!(Derivation::parse("").source, Source::Unknown);
+    }
+
+    #[test]
+    fn ancestors_and_descendants() {
+        let model = |name: &str, parent: &str| ParsedModel {
+            name: name.to_string(),
+            derived_from: format!(
+                "https://smart-data-models.github.io/dataModel.Device/{parent}/schema.json"
+            ),
+            ..Default::default()
+        };
+        let models = [
+            model("Device", "Device"),
+            model("DeviceModel", "Device"),
+            model("SmartMeter", "DeviceModel"),
+        ];
+
+        let tree = DerivationTree::new(&models);
+
+        assert_eq!(tree.ancestors("SmartMeter"), vec!["DeviceModel", "Device"]);
+        assert_eq!(
+            tree.descendants("Device"),
+            vec![
+                (1, "DeviceModel".to_string()),
+                (2, "SmartMeter".to_string())
+            ]
+        );
+    }
+}
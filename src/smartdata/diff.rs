@@ -0,0 +1,366 @@
+use serde::Serialize;
+
+use crate::{
+    smartdata::models::{Model, ParsedModel, Property},
+    ModelData,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Breaking,
+    Compatible,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    PropertyAdded,
+    PropertyRemoved,
+    TypeChanged,
+    FormatChanged,
+    EnumNarrowed,
+    EnumWidened,
+    NewlyRequired,
+    NoLongerRequired,
+    NgsiTypeChanged,
+    UnitsChanged,
+    ConstraintsTightened,
+    ConstraintsLoosened,
+}
+
+impl ChangeKind {
+    pub fn str(&self) -> &'static str {
+        match self {
+            ChangeKind::PropertyAdded => "property added",
+            ChangeKind::PropertyRemoved => "property removed",
+            ChangeKind::TypeChanged => "type changed",
+            ChangeKind::FormatChanged => "format changed",
+            ChangeKind::EnumNarrowed => "enum narrowed",
+            ChangeKind::EnumWidened => "enum widened",
+            ChangeKind::NewlyRequired => "newly required",
+            ChangeKind::NoLongerRequired => "no longer required",
+            ChangeKind::NgsiTypeChanged => "x-ngsi type changed",
+            ChangeKind::UnitsChanged => "units changed",
+            ChangeKind::ConstraintsTightened => "constraints tightened",
+            ChangeKind::ConstraintsLoosened => "constraints loosened",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            ChangeKind::PropertyAdded
+            | ChangeKind::EnumWidened
+            | ChangeKind::NoLongerRequired
+            | ChangeKind::ConstraintsLoosened => Severity::Compatible,
+            ChangeKind::PropertyRemoved
+            | ChangeKind::TypeChanged
+            | ChangeKind::FormatChanged
+            | ChangeKind::EnumNarrowed
+            | ChangeKind::NewlyRequired
+            | ChangeKind::NgsiTypeChanged
+            | ChangeKind::UnitsChanged
+            | ChangeKind::ConstraintsTightened => Severity::Breaking,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub property: String,
+    pub kind: ChangeKind,
+    pub severity: Severity,
+    pub detail: String,
+}
+
+impl Change {
+    fn new(property: &str, kind: ChangeKind, detail: String) -> Self {
+        Change {
+            property: property.to_string(),
+            kind,
+            severity: kind.severity(),
+            detail,
+        }
+    }
+}
+
+/// The machine readable report of the changes between two versions of a model.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDiff {
+    pub repo: String,
+    pub model: String,
+    pub old_ref: String,
+    pub new_ref: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub breaking: bool,
+    pub changes: Vec<Change>,
+}
+
+impl ModelDiff {
+    /// Fetches the model at both refs and diffs them.
+    pub async fn fetch(
+        repo: &str,
+        name: &str,
+        old_ref: &str,
+        new_ref: &str,
+    ) -> Result<Self, anyhow::Error> {
+        let old = fetch_at(repo, name, old_ref).await?;
+        let new = fetch_at(repo, name, new_ref).await?;
+        Ok(ModelDiff::new(&old, &new, old_ref, new_ref))
+    }
+
+    pub fn new(old: &ParsedModel, new: &ParsedModel, old_ref: &str, new_ref: &str) -> Self {
+        let mut changes = vec![];
+
+        for old_property in old.properties.iter() {
+            let name = old_property.name.as_str();
+            match new.property(name) {
+                Some(new_property) => diff_property(name, old_property, new_property, &mut changes),
+                None => changes.push(Change::new(
+                    name,
+                    ChangeKind::PropertyRemoved,
+                    String::from("the property does not exist anymore"),
+                )),
+            }
+        }
+
+        for new_property in new.properties.iter() {
+            let name = new_property.name.as_str();
+            if old.property(name).is_some() {
+                continue;
+            }
+
+            if new_property.required {
+                changes.push(Change::new(
+                    name,
+                    ChangeKind::NewlyRequired,
+                    String::from("new property which is required"),
+                ));
+            } else {
+                changes.push(Change::new(
+                    name,
+                    ChangeKind::PropertyAdded,
+                    String::from("new optional property"),
+                ));
+            }
+        }
+
+        changes.sort_by(|a, b| a.property.cmp(&b.property));
+        let breaking = changes.iter().any(|c| c.severity == Severity::Breaking);
+
+        ModelDiff {
+            repo: new.repo.clone(),
+            model: new.name.clone(),
+            old_ref: old_ref.to_string(),
+            new_ref: new_ref.to_string(),
+            old_version: old.version.clone(),
+            new_version: new.version.clone(),
+            breaking,
+            changes,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("diff is always serializable")
+    }
+}
+
+async fn fetch_at(repo: &str, name: &str, git_ref: &str) -> Result<ParsedModel, anyhow::Error> {
+    let model_data = ModelData {
+        repo: repo.to_string(),
+        name: name.to_string(),
+        git_ref: git_ref.to_string(),
+    };
+    Model::fetch_and_parse(&model_data).await
+}
+
+fn diff_property(name: &str, old: &Property, new: &Property, changes: &mut Vec<Change>) {
+    fn show<T: std::fmt::Debug>(value: &Option<T>) -> String {
+        match value {
+            Some(value) => format!("{value:?}"),
+            None => String::from("none"),
+        }
+    }
+
+    if old.typ != new.typ {
+        let detail = format!("{} -> {}", show(&old.typ), show(&new.typ));
+        changes.push(Change::new(name, ChangeKind::TypeChanged, detail));
+    }
+
+    if old.format != new.format {
+        let detail = format!("{} -> {}", show(&old.format), show(&new.format));
+        changes.push(Change::new(name, ChangeKind::FormatChanged, detail));
+    }
+
+    if old.ngsi_type() != new.ngsi_type() {
+        let detail = format!("{:?} -> {:?}", old.ngsi_type(), new.ngsi_type());
+        changes.push(Change::new(name, ChangeKind::NgsiTypeChanged, detail));
+    }
+
+    if old.units() != new.units() {
+        let detail = format!("{:?} -> {:?}", old.units(), new.units());
+        changes.push(Change::new(name, ChangeKind::UnitsChanged, detail));
+    }
+
+    match (&old.enums, &new.enums) {
+        (Some(old_enums), Some(new_enums)) => {
+            let removed: Vec<_> = old_enums
+                .iter()
+                .filter(|e| !new_enums.contains(e))
+                .collect();
+            let added: Vec<_> = new_enums
+                .iter()
+                .filter(|e| !old_enums.contains(e))
+                .collect();
+            if !removed.is_empty() {
+                let detail = format!("removed {removed:?}");
+                changes.push(Change::new(name, ChangeKind::EnumNarrowed, detail));
+            }
+            if !added.is_empty() {
+                let detail = format!("added {added:?}");
+                changes.push(Change::new(name, ChangeKind::EnumWidened, detail));
+            }
+        }
+        (None, Some(new_enums)) => {
+            let detail = format!("restricted to {new_enums:?}");
+            changes.push(Change::new(name, ChangeKind::EnumNarrowed, detail));
+        }
+        (Some(_), None) => {
+            let detail = String::from("any value is allowed");
+            changes.push(Change::new(name, ChangeKind::EnumWidened, detail));
+        }
+        (None, None) => {}
+    }
+
+    if !old.required && new.required {
+        let detail = String::from("the property is required now");
+        changes.push(Change::new(name, ChangeKind::NewlyRequired, detail));
+    } else if old.required && !new.required {
+        let detail = String::from("the property is optional now");
+        changes.push(Change::new(name, ChangeKind::NoLongerRequired, detail));
+    }
+
+    let old_constraints = old.constraints();
+    let new_constraints = new.constraints();
+    if old_constraints != new_constraints {
+        let kind = if constraints_tightened(old, new) {
+            ChangeKind::ConstraintsTightened
+        } else {
+            ChangeKind::ConstraintsLoosened
+        };
+        let detail = format!("{old_constraints:?} -> {new_constraints:?}");
+        changes.push(Change::new(name, kind, detail));
+    }
+}
+
+/// A constraint is tightened, if any value which was valid before may be invalid now.
+fn constraints_tightened(old: &Property, new: &Property) -> bool {
+    fn raised<T: PartialOrd>(old: Option<T>, new: Option<T>) -> bool {
+        match (old, new) {
+            (Some(old), Some(new)) => new > old,
+            (None, Some(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn lowered<T: PartialOrd>(old: Option<T>, new: Option<T>) -> bool {
+        match (old, new) {
+            (Some(old), Some(new)) => new < old,
+            (None, Some(_)) => true,
+            _ => false,
+        }
+    }
+
+    raised(old.minimum, new.minimum)
+        || lowered(old.maximum, new.maximum)
+        || raised(old.min_length, new.min_length)
+        || lowered(old.max_length, new.max_length)
+        || (new.pattern.is_some() && old.pattern != new.pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property};
+
+    use super::{ChangeKind, ModelDiff};
+
+    fn property(name: &str, typ: &str) -> Property {
+        Property {
+            name: name.to_string(),
+            typ: Some(typ.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn kinds(diff: &ModelDiff) -> Vec<(&str, ChangeKind)> {
+        diff.changes
+            .iter()
+            .map(|c| (c.property.as_str(), c.kind))
+            .collect()
+    }
+
+    #[test]
+    fn breaking_changes() {
+        let mut status = property("status", "string");
+        status.enums = Some(vec!["free".to_string(), "occupied".to_string()]);
+        let old = ParsedModel {
+            name: "ParkingSpot".to_string(),
+            properties: vec![
+                property("width", "number"),
+                property("name", "string"),
+                status.clone(),
+            ],
+            ..Default::default()
+        };
+
+        status.enums = Some(vec!["free".to_string()]);
+        let mut name = property("name", "string");
+        name.required = true;
+        let new = ParsedModel {
+            name: "ParkingSpot".to_string(),
+            properties: vec![property("width", "string"), name, status],
+            ..Default::default()
+        };
+
+        let diff = ModelDiff::new(&old, &new, "v1", "v2");
+
+        assert!(diff.breaking);
+        assert_eq!(
+            kinds(&diff),
+            vec![
+                ("name", ChangeKind::NewlyRequired),
+                ("status", ChangeKind::EnumNarrowed),
+                ("width", ChangeKind::TypeChanged),
+            ]
+        );
+    }
+
+    #[test]
+    fn compatible_changes() {
+        let mut length = property("length", "number");
+        length.maximum = Some(10.0);
+        let old = ParsedModel {
+            properties: vec![length.clone()],
+            ..Default::default()
+        };
+
+        length.maximum = Some(20.0);
+        let new = ParsedModel {
+            properties: vec![length, property("color", "string")],
+            ..Default::default()
+        };
+
+        let diff = ModelDiff::new(&old, &new, "v1", "v2");
+
+        assert!(!diff.breaking);
+        assert_eq!(
+            kinds(&diff),
+            vec![
+                ("color", ChangeKind::PropertyAdded),
+                ("length", ChangeKind::ConstraintsLoosened),
+            ]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::smartdata::models::data_model_raw;
+
+/// The example payloads every data model ships in its `examples` folder.
+pub const EXAMPLE_FILES: [&str; 4] = [
+    "example.json",
+    "example-normalized.json",
+    "example.jsonld",
+    "example-normalized.jsonld",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub file: String,
+    pub body: String,
+}
+
+impl Example {
+    /// Fetches all official examples of a model, files which do not exist are skipped.
+    pub async fn fetch_all(
+        repo_name: &str,
+        name: &str,
+        git_ref: &str,
+    ) -> Result<Vec<Self>, reqwest::Error> {
+        let mut examples = Vec::with_capacity(EXAMPLE_FILES.len());
+        for file in EXAMPLE_FILES {
+            let url = data_model_raw(repo_name, name, git_ref, &format!("examples/{file}"));
+            let res = reqwest::get(url).await?;
+            if !res.status().is_success() {
+                continue;
+            }
+
+            let body = res.text().await?;
+            examples.push(Example {
+                file: file.to_string(),
+                body: pretty_json(&body),
+            });
+        }
+
+        Ok(examples)
+    }
+}
+
+/// Fetches the README of a model which contains the notes of the model authors.
+pub async fn fetch_readme(
+    repo_name: &str,
+    name: &str,
+    git_ref: &str,
+) -> Result<String, reqwest::Error> {
+    let url = data_model_raw(repo_name, name, git_ref, "README.md");
+    reqwest::get(url).await?.error_for_status()?.text().await
+}
+
+fn pretty_json(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| body.to_string())
+}
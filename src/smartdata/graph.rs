@@ -0,0 +1,173 @@
+use std::collections::BTreeSet;
+
+use crate::smartdata::models::{ParsedModel, Property};
+
+/// A relationship from a property of one model to another entity type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub property: String,
+    /// Whether `to` is the name of a model of the catalog.
+    pub resolved: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Graph {
+    pub nodes: Vec<String>,
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    /// Builds the relationship graph of `models`, targets are matched against `catalog`.
+    pub fn new(models: &[ParsedModel], catalog: &[String]) -> Self {
+        let mut edges = vec![];
+        for model in models {
+            for property in model.properties.iter() {
+                if let Some(edge) = edge(model, property, catalog) {
+                    edges.push(edge);
+                }
+            }
+        }
+
+        let nodes: BTreeSet<_> = models
+            .iter()
+            .map(|model| model.name.clone())
+            .chain(edges.iter().map(|edge| edge.to.clone()))
+            .collect();
+
+        Graph {
+            nodes: nodes.into_iter().collect(),
+            edges,
+        }
+    }
+}
+
+fn edge(model: &ParsedModel, property: &Property, catalog: &[String]) -> Option<Edge> {
+    let target = target_name(&property.name);
+    let resolved = resolve(&target, catalog);
+
+    // An uri is only a relationship, if it names another entity type of the catalog
+    let is_entity_uri = has_uri_format(property) && resolved.is_some();
+    if relationship_target(property).is_none() && !is_entity_uri {
+        return None;
+    }
+
+    Some(Edge {
+        from: model.name.clone(),
+        to: resolved.clone().unwrap_or(target),
+        property: property.name.clone(),
+        resolved: resolved.is_some(),
+    })
+}
+
+/// The entity type a relationship property points to, e.g. `refDevice` points to `Device`.
+/// Returns `None` if the property is no relationship.
+pub fn relationship_target(property: &Property) -> Option<String> {
+    let is_ref = property
+        .name
+        .strip_prefix("ref")
+        .is_some_and(|rest| rest.starts_with(char::is_uppercase));
+    let is_relationship = property.ngsi_type() == Some("Relationship");
+
+    (is_ref || is_relationship).then(|| target_name(&property.name))
+}
+
+/// Names like `refDevice` or `hasBuilding` point to `Device` and `Building`.
+fn target_name(name: &str) -> String {
+    ["ref", "has"]
+        .iter()
+        .find_map(|prefix| {
+            name.strip_prefix(prefix)
+                .filter(|rest| rest.starts_with(char::is_uppercase))
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| capitalize(name))
+}
+
+/// Finds `target` in the catalog, ignoring case and plural forms like `refDevices`.
+fn resolve(target: &str, catalog: &[String]) -> Option<String> {
+    let singular = target.strip_suffix('s').unwrap_or(target);
+    catalog
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(target))
+        .or_else(|| {
+            catalog
+                .iter()
+                .find(|name| name.eq_ignore_ascii_case(singular))
+        })
+        .cloned()
+}
+
+fn has_uri_format(property: &Property) -> bool {
+    if property.format.as_deref() == Some("uri") {
+        return true;
+    }
+
+    property
+        .any_of
+        .iter()
+        .chain(property.one_of.iter())
+        .flatten()
+        .any(|variant| variant.get("format").and_then(|f| f.as_str()) == Some("uri"))
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smartdata::models::{ParsedModel, Property, XNgsi};
+
+    use super::{Edge, Graph};
+
+    #[test]
+    fn relationship_edges() {
+        let property = |name: &str, ngsi_type: Option<&str>| Property {
+            name: name.to_string(),
+            xngsi: ngsi_type.map(|typ| XNgsi {
+                typ: Some(typ.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let device = ParsedModel {
+            name: "Device".to_string(),
+            properties: vec![
+                property("refDeviceModel", None),
+                property("owner", Some("Relationship")),
+                property("name", Some("Property")),
+                property("refBuildings", None),
+            ],
+            ..Default::default()
+        };
+        let catalog = vec![
+            "Device".to_string(),
+            "DeviceModel".to_string(),
+            "Building".to_string(),
+        ];
+
+        let graph = Graph::new(&[device], &catalog);
+
+        assert_eq!(
+            graph.nodes,
+            vec!["Building", "Device", "DeviceModel", "Owner"]
+        );
+        assert_eq!(
+            graph.edges[0],
+            Edge {
+                from: "Device".to_string(),
+                to: "DeviceModel".to_string(),
+                property: "refDeviceModel".to_string(),
+                resolved: true,
+            }
+        );
+        assert!(!graph.edges[1].resolved);
+        assert_eq!(graph.edges[2].to, "Building");
+    }
+}
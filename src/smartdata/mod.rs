@@ -1 +1,7 @@
+pub mod compare;
+pub mod derivation;
+pub mod diff;
+pub mod examples;
+pub mod graph;
 pub mod models;
+pub mod refs;
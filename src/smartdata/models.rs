@@ -3,17 +3,31 @@ use std::{cmp::Ordering, collections::BTreeMap};
 use dioxus::prelude::*;
 use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::ModelData;
+use crate::{
+    smartdata::refs::{resolve_ref, DEFAULT_REF},
+    ModelData,
+};
 
-const OFFICIAL_LIST_LINK: &str = "https://raw.githubusercontent.com\
-/smart-data-models/data-models/master/specs/AllSubjects/official_list_data_models.json";
+pub fn official_list_link(git_ref: &str) -> String {
+    format!("https://raw.githubusercontent.com/smart-data-models/data-models/{git_ref}/specs/AllSubjects/official_list_data_models.json")
+}
+
+pub fn data_model_yaml(data_model: &str, repo: &str, git_ref: &str) -> String {
+    data_model_raw(data_model, repo, git_ref, "model.yaml")
+}
+
+/// Raw link to a file at `git_ref` which lives next to the `model.yaml` of a data model.
+pub fn data_model_raw(repo_name: &str, name: &str, git_ref: &str, file: &str) -> String {
+    format!("https://raw.githubusercontent.com/smart-data-models/dataModel.{repo_name}/{git_ref}/{name}/{file}")
+}
 
-pub fn data_model_yaml(data_model: &str, repo: &str) -> String {
-    format!("https://raw.githubusercontent.com/smart-data-models/dataModel.{data_model}/master/{repo}/model.yaml")
+pub fn data_model_github(repo_name: &str, name: &str, git_ref: &str) -> String {
+    format!("https://github.com/smart-data-models/dataModel.{repo_name}/tree/{git_ref}/{name}",)
 }
 
-pub fn data_model_github(repo_name: &str, name: &str) -> String {
-    format!("https://github.com/smart-data-models/dataModel.{repo_name}/tree/master/{name}",)
+/// Fetches a file, an error status is an error as well.
+pub async fn fetch_text(url: &str) -> Result<String, anyhow::Error> {
+    Ok(reqwest::get(url).await?.error_for_status()?.text().await?)
 }
 
 #[derive(Deserialize, Serialize, Debug, Props, PartialEq, Clone)]
@@ -22,18 +36,38 @@ pub struct ModelList {
     pub date: String,
     #[serde(rename = "officialList")]
     pub entries: Vec<DataModelRepo>,
+    #[serde(skip)]
+    pub git_ref: String,
 }
 
 impl ModelList {
-    pub async fn fetch() -> Result<Self, reqwest::Error> {
-        let model_list = reqwest::get(OFFICIAL_LIST_LINK)
+    pub async fn fetch(git_ref: &str) -> Result<Self, anyhow::Error> {
+        let git_ref = resolve_ref("data-models", git_ref).await?;
+        let mut model_list = reqwest::get(official_list_link(&git_ref))
             .await?
+            .error_for_status()?
             .json::<ModelList>()
             .await?;
+        model_list.git_ref = git_ref;
 
         Ok(model_list)
     }
 
+    /// The names of all models of the catalog.
+    pub fn model_names(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .flat_map(|dmr| dmr.data_models.iter().cloned())
+            .collect()
+    }
+
+    /// The repo which contains the model called `name`.
+    pub fn repo_of(&self, name: &str) -> Option<&DataModelRepo> {
+        self.entries
+            .iter()
+            .find(|dmr| dmr.data_models.iter().any(|n| n == name))
+    }
+
     pub fn get_filtered_entries(&self, filter: &str) -> Vec<DataModelRepo> {
         fn in_name_or_children(data_model_repo: &DataModelRepo, filter: &str) -> bool {
             let in_names = data_model_repo.name.contains(filter);
@@ -79,9 +113,10 @@ where
     Ok(name.to_string())
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Default, Debug, Clone, Deserialize, PartialEq)]
 pub struct ParsedModel {
     pub name: String,
+    pub repo: String,
     pub description: String,
     pub properties: Vec<Property>,
     pub required: Vec<String>,
@@ -93,6 +128,11 @@ pub struct ParsedModel {
     pub tags: String,
     pub version: String,
     pub url: String,
+    pub raw: String,
+    /// The ref which was asked for, e.g. a tag, a sha or a date.
+    pub git_ref: String,
+    /// The ref the model was actually fetched at, dates are resolved to a commit sha.
+    pub resolved_ref: String,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -118,23 +158,37 @@ pub struct Model {
     pub url: String,
     #[serde(skip_deserializing)]
     pub name: String,
+    #[serde(skip_deserializing)]
+    pub repo: String,
+    #[serde(skip_deserializing)]
+    pub raw: String,
+    #[serde(skip_deserializing)]
+    pub git_ref: String,
+    #[serde(skip_deserializing)]
+    pub resolved_ref: String,
 }
 
 impl Model {
-    pub async fn fetch(data_model_data: &ModelData) -> Result<Self, reqwest::Error> {
+    pub async fn fetch(data_model_data: &ModelData) -> Result<Self, anyhow::Error> {
         let ModelData {
             repo: repo_name,
             name,
-            url,
+            git_ref,
         } = data_model_data;
 
-        let body = reqwest::get(url).await?.text().await?;
+        let resolved_ref = resolve_ref(&format!("dataModel.{repo_name}"), git_ref).await?;
+        let url = data_model_yaml(repo_name, name, &resolved_ref);
+        let body = fetch_text(&url).await?;
 
-        let mut yaml: BTreeMap<String, Model> = serde_yaml::from_str(&body).unwrap();
+        let mut yaml: BTreeMap<String, Model> = serde_yaml::from_str(&body)?;
         let (_, mut model) = yaml.pop_first().expect("we have a object layer");
 
-        model.url = data_model_github(repo_name, name);
+        model.url = data_model_github(repo_name, name, &resolved_ref);
         model.name = name.clone();
+        model.repo = repo_name.clone();
+        model.raw = body;
+        model.git_ref = git_ref.clone();
+        model.resolved_ref = resolved_ref;
 
         Ok(model)
     }
@@ -185,6 +239,7 @@ impl Model {
 
         ParsedModel {
             name: self.name,
+            repo: self.repo,
             description: self.description,
             properties,
             required: self.required,
@@ -196,6 +251,9 @@ impl Model {
             tags: self.tags,
             version: self.version,
             url: self.url,
+            raw: self.raw,
+            git_ref: self.git_ref,
+            resolved_ref: self.resolved_ref,
         }
     }
 
@@ -257,11 +315,189 @@ pub struct Property {
     pub typ: Option<String>,
     #[serde(rename = "x-ngsi")]
     pub xngsi: Option<XNgsi>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    #[serde(rename = "minLength")]
+    pub min_length: Option<u64>,
+    #[serde(rename = "maxLength")]
+    pub max_length: Option<u64>,
+    pub pattern: Option<String>,
+    pub items: Option<serde_yaml::Value>,
+    /// Definitions of the members of an object property.
+    pub properties: Option<serde_yaml::Value>,
+    /// The `required` list of an object property.
+    #[serde(rename = "required")]
+    pub required_members: Option<serde_yaml::Value>,
+    /// The chosen type of properties which can be one of several types.
+    pub variant: Option<String>,
     pub checked: bool,
     pub name: String,
+    /// Whether the model requires the property, set from the `required` list of the model.
+    #[serde(skip_deserializing)]
     pub required: bool,
 }
 
+impl Property {
+    /// A checked property named `name` with the definition `yaml`, as tests write them.
+    #[cfg(test)]
+    pub fn from_yaml(name: &str, yaml: &str) -> Self {
+        Property {
+            name: name.to_string(),
+            checked: true,
+            ..serde_yaml::from_str(yaml).unwrap()
+        }
+    }
+
+    /// Returns the validation keywords of the property in a displayable form.
+    pub fn constraints(&self) -> Vec<(&'static str, String)> {
+        let mut constraints = vec![];
+        if let Some(minimum) = self.minimum {
+            constraints.push(("minimum", minimum.to_string()));
+        }
+        if let Some(maximum) = self.maximum {
+            constraints.push(("maximum", maximum.to_string()));
+        }
+        if let Some(min_length) = self.min_length {
+            constraints.push(("minLength", min_length.to_string()));
+        }
+        if let Some(max_length) = self.max_length {
+            constraints.push(("maxLength", max_length.to_string()));
+        }
+        if let Some(pattern) = &self.pattern {
+            constraints.push(("pattern", pattern.clone()));
+        }
+        constraints
+    }
+
+    /// The json types of the property, types of `anyOf` and `oneOf` variants are collected.
+    pub fn json_types(&self) -> Vec<&str> {
+        if let Some(typ) = &self.typ {
+            return vec![typ.as_str()];
+        }
+
+        let mut types = vec![];
+        for variant in self.any_of.iter().chain(self.one_of.iter()).flatten() {
+            if let Some(typ) = variant.get("type").and_then(|typ| typ.as_str()) {
+                if !types.contains(&typ) {
+                    types.push(typ);
+                }
+            }
+        }
+        types
+    }
+
+    /// The definition of the elements of an array property.
+    pub fn item(&self) -> Option<Property> {
+        serde_yaml::from_value(self.items.clone()?).ok()
+    }
+
+    /// The members of an object property, with their names and required flags set.
+    pub fn members(&self) -> Vec<Property> {
+        let Some(serde_yaml::Value::Mapping(members)) = &self.properties else {
+            return vec![];
+        };
+        let required = self.nested_required();
+
+        members
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = name.as_str()?;
+                let mut member: Property = serde_yaml::from_value(value.clone()).ok()?;
+                member.name = name.to_string();
+                member.required = required.iter().any(|r| r == name);
+                member.checked = true;
+                Some(member)
+            })
+            .collect()
+    }
+
+    fn nested_required(&self) -> Vec<String> {
+        self.required_members
+            .as_ref()
+            .and_then(|required| serde_yaml::from_value(required.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether the property holds a GeoJSON geometry.
+    pub fn is_geo(&self) -> bool {
+        self.ngsi_type() == Some("GeoProperty")
+            || self.name == "location"
+            || self
+                .variant
+                .as_deref()
+                .is_some_and(|variant| GeoProperty::array().iter().any(|geo| geo.str() == variant))
+    }
+
+    pub fn ngsi_type(&self) -> Option<&str> {
+        self.xngsi.as_ref().and_then(|xngsi| xngsi.typ.as_deref())
+    }
+
+    pub fn ngsi_model(&self) -> Option<&str> {
+        self.xngsi.as_ref().and_then(|xngsi| xngsi.model.as_deref())
+    }
+
+    pub fn units(&self) -> Option<&str> {
+        self.xngsi.as_ref().and_then(|xngsi| xngsi.units.as_deref())
+    }
+
+    /// Names the parts of the definition in which `other` differs from this property.
+    /// Descriptions are ignored, they differ between almost all models.
+    pub fn differences(&self, other: &Property) -> Vec<&'static str> {
+        let mut differences = vec![];
+        if self.typ != other.typ {
+            differences.push("type");
+        }
+        if self.format != other.format {
+            differences.push("format");
+        }
+        if self.enums != other.enums {
+            differences.push("enum");
+        }
+        if self.ngsi_type() != other.ngsi_type() {
+            differences.push("x-ngsi type");
+        }
+        if self.ngsi_model() != other.ngsi_model() {
+            differences.push("x-ngsi model");
+        }
+        if self.units() != other.units() {
+            differences.push("units");
+        }
+        if self.constraints() != other.constraints() {
+            differences.push("constraints");
+        }
+        differences
+    }
+}
+
+impl ParsedModel {
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+
+    /// The ref the model was fetched at, dates are resolved to their commit.
+    pub fn fetched_ref(&self) -> &str {
+        [&self.resolved_ref, &self.git_ref]
+            .into_iter()
+            .find(|git_ref| !git_ref.is_empty())
+            .map_or(DEFAULT_REF, String::as_str)
+    }
+
+    /// Raw link to the `model.yaml` the model was parsed from.
+    pub fn yaml_link(&self) -> String {
+        data_model_yaml(&self.repo, &self.name, self.fetched_ref())
+    }
+
+    /// Cuts the definition of a single property out of the raw `model.yaml`.
+    pub fn raw_property(&self, name: &str) -> Option<String> {
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&self.raw).ok()?;
+        let (_, model) = yaml.as_mapping()?.iter().next()?;
+        let property = model.get("properties")?.get(name)?;
+        serde_yaml::to_string(property).ok()
+    }
+}
+
 #[derive(Default, PartialEq, Debug, Deserialize, Serialize, Clone)]
 #[serde(default)]
 pub struct XNgsi {
@@ -303,3 +539,38 @@ impl GeoProperty {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ParsedModel, Property};
+
+    #[test]
+    fn compare_definitions() {
+        let speed = Property::from_yaml(
+            "speed",
+            "{type: number, minimum: 0, x-ngsi: {model: 'https://schema.org/Number', units: KMH}}",
+        );
+        assert_eq!(speed.constraints(), [("minimum", "0".to_string())]);
+        assert!(speed.differences(&speed).is_empty());
+
+        let other = Property::from_yaml(
+            "speed",
+            "{type: number, maximum: 300, x-ngsi: {model: 'https://schema.org/Float', units: KMH}}",
+        );
+        assert_eq!(speed.differences(&other), ["x-ngsi model", "constraints"]);
+    }
+
+    #[test]
+    fn cut_raw_property() {
+        let model = ParsedModel {
+            raw: "Vehicle:\n  properties:\n    speed:\n      type: number\n      minimum: 0\n"
+                .to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            model.raw_property("speed").unwrap(),
+            "type: number\nminimum: 0\n"
+        );
+        assert_eq!(model.raw_property("heading"), None);
+    }
+}
@@ -0,0 +1,93 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, MutexGuard},
+};
+
+use serde::Deserialize;
+
+pub const DEFAULT_REF: &str = "master";
+
+/// Shas of the dates resolved so far by repository and date, so loading many models of a date
+/// costs one request per repository of the rate limited GitHub API.
+static RESOLVED_DATES: Mutex<BTreeMap<(String, String), String>> = Mutex::new(BTreeMap::new());
+
+#[derive(Deserialize)]
+struct Commit {
+    sha: String,
+}
+
+/// Resolves a date (`YYYY-MM-DD`) to the last commit of `repo` on that day.
+/// Every other ref (branch, tag or commit sha) can be used by raw.githubusercontent.com directly.
+pub async fn resolve_ref(repo: &str, git_ref: &str) -> Result<String, anyhow::Error> {
+    if !is_date(git_ref) {
+        return Ok(git_ref.to_string());
+    }
+    let key = (repo.to_string(), git_ref.to_string());
+    if let Some(sha) = resolved_dates().get(&key) {
+        return Ok(sha.clone());
+    }
+
+    let url = format!(
+        "https://api.github.com/repos/smart-data-models/{repo}/commits\
+        ?until={git_ref}T23:59:59Z&per_page=1"
+    );
+    let commits = reqwest::Client::builder()
+        .user_agent(env!("CARGO_PKG_NAME"))
+        .build()?
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Vec<Commit>>()
+        .await?;
+
+    let Some(commit) = commits.into_iter().next() else {
+        anyhow::bail!("{repo} has no commits before {git_ref}");
+    };
+    resolved_dates().insert(key, commit.sha.clone());
+    Ok(commit.sha)
+}
+
+fn resolved_dates() -> MutexGuard<'static, BTreeMap<(String, String), String>> {
+    // A panic while holding the lock can not leave the map half written
+    RESOLVED_DATES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn is_date(git_ref: &str) -> bool {
+    let parts: Vec<_> = git_ref.split('-').collect();
+    matches!(parts.as_slice(), [year, month, day]
+        if year.len() == 4 && month.len() == 2 && day.len() == 2
+        && parts.iter().all(|part| part.chars().all(|c| c.is_ascii_digit())))
+}
+
+/// Shortens commit shas for displaying, other refs are returned unchanged.
+pub fn short_ref(git_ref: &str) -> &str {
+    let is_sha = git_ref.len() == 40 && git_ref.chars().all(|c| c.is_ascii_hexdigit());
+    if is_sha {
+        &git_ref[..7]
+    } else {
+        git_ref
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_date, short_ref};
+
+    #[test]
+    fn dates() {
+        assert!(is_date("2024-03-01"));
+        assert!(!is_date("v1.0.0"));
+        assert!(!is_date("master"));
+        assert!(!is_date("2024-3-1"));
+    }
+
+    #[test]
+    fn short_shas() {
+        let sha = "8d1e1c5b9c2f4e5a6b7c8d9e0f1a2b3c4d5e6f7a";
+        assert_eq!(short_ref(sha), "8d1e1c5");
+        assert_eq!(short_ref("v1.0.0"), "v1.0.0");
+    }
+}
@@ -0,0 +1,194 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    codegen::{
+        jsonschema::{self, Documents},
+        openapi, Language,
+    },
+    profile::Profile,
+    smartdata::{
+        models::{Model, ParsedModel},
+        refs::DEFAULT_REF,
+    },
+    ModelData,
+};
+
+/// A project file listing the models a project integrates, saved as a yaml file.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    #[serde(default)]
+    pub entries: Vec<WorkspaceEntry>,
+    /// Where the file was opened from or saved to.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceEntry {
+    pub repo: String,
+    pub model: String,
+    #[serde(rename = "ref", default = "default_ref")]
+    pub git_ref: String,
+    /// Name of a saved profile which selects the properties of the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+fn default_ref() -> String {
+    DEFAULT_REF.to_string()
+}
+
+impl WorkspaceEntry {
+    pub fn model_data(&self) -> ModelData {
+        ModelData {
+            repo: self.repo.clone(),
+            name: self.model.clone(),
+            git_ref: self.git_ref.clone(),
+        }
+    }
+
+    /// Fetches the model at its ref, without a profile only its required properties are checked.
+    async fn fetch(&self) -> Result<ParsedModel, anyhow::Error> {
+        let mut model = Model::fetch_and_parse(&self.model_data()).await?;
+        if let Some(profile) = &self.profile {
+            Profile::load_saved(profile)?.apply(&mut model);
+        }
+        Ok(model)
+    }
+}
+
+impl Workspace {
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let yaml = fs::read_to_string(path)?;
+        let mut workspace: Workspace = serde_yaml::from_str(&yaml)?;
+        workspace.path = Some(path.to_path_buf());
+        Ok(workspace)
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<(), anyhow::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_yaml::to_string(self)?)?;
+        self.path = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    pub fn contains(&self, model: &str) -> bool {
+        self.entries.iter().any(|entry| entry.model == model)
+    }
+
+    /// Adds the entry or replaces the entry of the same model.
+    pub fn add(&mut self, entry: WorkspaceEntry) {
+        match self.entries.iter_mut().find(|e| e.model == entry.model) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    pub fn remove(&mut self, model: &str) {
+        self.entries.retain(|entry| entry.model != model);
+    }
+
+    /// Fetches every entry at its ref and applies its profile. Entries which fail are skipped,
+    /// their errors are returned.
+    pub async fn fetch_models(&self) -> (Vec<ParsedModel>, Vec<String>) {
+        let mut models = Vec::with_capacity(self.entries.len());
+        let mut errors = vec![];
+        for entry in self.entries.iter() {
+            match entry.fetch().await {
+                Ok(model) => models.push(model),
+                Err(err) => errors.push(format!("{}: {err}", entry.model)),
+            }
+        }
+        (models, errors)
+    }
+
+    /// Writes the generated code of every entry to `output_dir`.
+    /// Returns the paths of the written files and the errors of the entries which failed.
+    pub async fn generate_all(
+        &self,
+        output_dir: &Path,
+        language: Language,
+    ) -> Result<(Vec<PathBuf>, Vec<String>), anyhow::Error> {
+        fs::create_dir_all(output_dir)?;
+
+        let (models, mut errors) = self.fetch_models().await;
+        let mut written = Vec::with_capacity(models.len());
+        for model in models {
+            let path = output_dir.join(format!("{}.{}", model.name, language.extension()));
+            // Schemas keep the numbering of the files they replace
+            let previous = fs::read_to_string(&path).ok();
+            let documents = match language {
+                Language::JsonSchema => jsonschema::fetch_documents(&model).await,
+                _ => Documents::new(),
+            };
+            let generated = language
+                .regenerate(&model, previous.as_deref(), &documents)
+                .and_then(|code| Ok(fs::write(&path, code)?));
+            match generated {
+                Ok(()) => written.push(path),
+                Err(err) => errors.push(format!("{}: {err}", model.name)),
+            }
+        }
+
+        Ok((written, errors))
+    }
+
+    /// Writes the components of all entries into one OpenAPI document in `output_dir`,
+    /// so relationships between them become `$ref`s. Returns its path and the errors of the
+    /// entries which are missing from it.
+    pub async fn export_openapi(
+        &self,
+        output_dir: &Path,
+    ) -> Result<(PathBuf, Vec<String>), anyhow::Error> {
+        fs::create_dir_all(output_dir)?;
+        let (models, errors) = self.fetch_models().await;
+
+        let path = output_dir.join("components.yaml");
+        fs::write(&path, openapi::generate(&models))?;
+        Ok((path, errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Workspace, WorkspaceEntry};
+
+    #[test]
+    fn parse_workspace() {
+        let yaml = "\
+entries:
+- repo: Weather
+  model: WeatherObserved
+  ref: v1.0.0
+  profile: minimal-sensor
+- repo: Device
+  model: Device
+";
+        let workspace: Workspace = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(
+            workspace.entries,
+            vec![
+                WorkspaceEntry {
+                    repo: "Weather".to_string(),
+                    model: "WeatherObserved".to_string(),
+                    git_ref: "v1.0.0".to_string(),
+                    profile: Some("minimal-sensor".to_string()),
+                },
+                WorkspaceEntry {
+                    repo: "Device".to_string(),
+                    model: "Device".to_string(),
+                    git_ref: "master".to_string(),
+                    profile: None,
+                },
+            ]
+        );
+    }
+}